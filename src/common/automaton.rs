@@ -0,0 +1,353 @@
+use std::collections::{HashSet, VecDeque};
+use std::hash::Hash;
+
+use super::dir::{Dir4, Dir8};
+use super::matrix::{Coord, MatrixMut};
+use super::point::{GridPoint, Point, PointN};
+use super::sparse::SparseGrid;
+
+/// Which cells influence a cell.
+pub trait Neighborhood<I> {
+    fn neighbors(&self, i: I) -> impl Iterator<Item = I>;
+
+    /// The fixed offsets of the neighbours, if they have any. A matrix
+    /// steps these through its `Boundary`, so they wrap on a torus.
+    fn deltas(&self) -> Option<Vec<Point>> {
+        None
+    }
+}
+
+/// The 8 (or `3^D - 1`) surrounding cells.
+#[derive(Clone, Copy, Debug)]
+pub struct Moore;
+
+/// The 4 (or `2 * D`) orthogonally adjacent cells.
+#[derive(Clone, Copy, Debug)]
+#[allow(dead_code)]
+pub struct VonNeumann;
+
+impl Neighborhood<Coord> for Moore {
+    fn neighbors(&self, c: Coord) -> impl Iterator<Item = Coord> {
+        Dir8::ALL.into_iter().filter_map(move |d| c + d)
+    }
+
+    fn deltas(&self) -> Option<Vec<Point>> {
+        Some(Dir8::ALL.map(Dir8::delta).to_vec())
+    }
+}
+
+impl Neighborhood<Coord> for VonNeumann {
+    fn neighbors(&self, c: Coord) -> impl Iterator<Item = Coord> {
+        Dir4::ALL.into_iter().filter_map(move |d| c + d)
+    }
+
+    fn deltas(&self) -> Option<Vec<Point>> {
+        Some(Dir4::ALL.map(Dir4::delta).to_vec())
+    }
+}
+
+impl Neighborhood<Point> for Moore {
+    fn neighbors(&self, p: Point) -> impl Iterator<Item = Point> {
+        p.neighbors(true)
+    }
+}
+
+impl Neighborhood<Point> for VonNeumann {
+    fn neighbors(&self, p: Point) -> impl Iterator<Item = Point> {
+        p.neighbors(false)
+    }
+}
+
+impl<const D: usize> Neighborhood<PointN<D>> for Moore {
+    fn neighbors(&self, p: PointN<D>) -> impl Iterator<Item = PointN<D>> {
+        p.neighbors(true)
+    }
+}
+
+impl<const D: usize> Neighborhood<PointN<D>> for VonNeumann {
+    fn neighbors(&self, p: PointN<D>) -> impl Iterator<Item = PointN<D>> {
+        p.neighbors(false)
+    }
+}
+
+impl<I, F, It> Neighborhood<I> for F
+where
+    F: Fn(I) -> It,
+    It: IntoIterator<Item = I>,
+{
+    fn neighbors(&self, i: I) -> impl Iterator<Item = I> {
+        self(i).into_iter()
+    }
+}
+
+/// Storage an `Automaton` can evolve.
+pub trait Cells: Clone {
+    type Index: Copy + Eq + Hash;
+    type Item: Clone + PartialEq;
+
+    /// `None` outside the storage.
+    fn cell(&self, i: Self::Index) -> Option<Self::Item>;
+    fn set(&mut self, i: Self::Index, v: Self::Item);
+    /// Neighbours of `i` in the storage.
+    fn adjacent<N: Neighborhood<Self::Index>>(
+        &self,
+        i: Self::Index,
+        neighborhood: &N,
+    ) -> Vec<Self::Index> {
+        neighborhood.neighbors(i).collect()
+    }
+    /// Cells a synchronous step has to recompute.
+    #[allow(dead_code)]
+    fn domain<N: Neighborhood<Self::Index>>(&self, neighborhood: &N) -> Vec<Self::Index>;
+}
+
+impl<M> Cells for M
+where
+    M: MatrixMut + Clone,
+    M::Item: PartialEq,
+{
+    type Index = Coord;
+    type Item = M::Item;
+
+    fn cell(&self, c: Coord) -> Option<M::Item> {
+        self.get_coord(c)
+    }
+
+    fn set(&mut self, c: Coord, v: M::Item) {
+        if let Some(cell) = self.get_coord_mut(c) {
+            *cell = v;
+        }
+    }
+
+    fn adjacent<N: Neighborhood<Coord>>(&self, c: Coord, neighborhood: &N) -> Vec<Coord> {
+        match neighborhood.deltas() {
+            Some(deltas) => self.neighbor_coords(c, deltas).collect(),
+            None => neighborhood.neighbors(c).collect(),
+        }
+    }
+
+    fn domain<N: Neighborhood<Coord>>(&self, _: &N) -> Vec<Coord> {
+        self.flat_iter().map(|(c, _)| c).collect()
+    }
+}
+
+/// Cells equal to the default are kept unset, so the grid stays sparse.
+impl<T, P> Cells for SparseGrid<T, P>
+where
+    T: Clone + PartialEq,
+    P: GridPoint,
+{
+    type Index = P;
+    type Item = T;
+
+    fn cell(&self, p: P) -> Option<T> {
+        Some(self.get(p).clone())
+    }
+
+    fn set(&mut self, p: P, v: T) {
+        if v == *self.default_value() {
+            self.remove(p);
+        } else {
+            self.insert(p, v);
+        }
+    }
+
+    /// Set cells and everything next to them.
+    fn domain<N: Neighborhood<P>>(&self, neighborhood: &N) -> Vec<P> {
+        self.iter()
+            .flat_map(|(p, _)| neighborhood.neighbors(p).chain([p]))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect()
+    }
+}
+
+pub struct Automaton<S: Cells, N> {
+    cells: S,
+    #[allow(dead_code)]
+    back: Option<S>,
+    neighborhood: N,
+    #[allow(dead_code)]
+    generation: usize,
+}
+
+impl<S, N> Automaton<S, N>
+where
+    S: Cells,
+    N: Neighborhood<S::Index>,
+{
+    pub fn new(cells: S, neighborhood: N) -> Self {
+        Automaton {
+            cells,
+            back: None,
+            neighborhood,
+            generation: 0,
+        }
+    }
+
+    pub fn cells(&self) -> &S {
+        &self.cells
+    }
+
+    pub fn cells_mut(&mut self) -> &mut S {
+        &mut self.cells
+    }
+
+    #[allow(dead_code)]
+    pub fn into_cells(self) -> S {
+        self.cells
+    }
+
+    /// Number of synchronous steps taken so far.
+    #[allow(dead_code)]
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Updates every cell at once from the current state, passing `rule` the
+    /// cell and its in-bounds neighbours. Returns how many cells changed.
+    #[allow(dead_code)]
+    pub fn step<R>(&mut self, mut rule: R) -> usize
+    where
+        R: FnMut(&S::Item, &[S::Item]) -> S::Item,
+    {
+        let mut back = self.back.take().unwrap_or_else(|| self.cells.clone());
+        back.clone_from(&self.cells);
+        let mut neighbors = vec![];
+        let mut changed = 0;
+        for i in self.cells.domain(&self.neighborhood) {
+            let Some(cur) = self.cells.cell(i) else {
+                continue;
+            };
+            neighbors.clear();
+            neighbors.extend(
+                self.cells
+                    .adjacent(i, &self.neighborhood)
+                    .into_iter()
+                    .filter_map(|n| self.cells.cell(n)),
+            );
+            let next = rule(&cur, &neighbors);
+            if next != cur {
+                back.set(i, next);
+                changed += 1;
+            }
+        }
+        self.back = Some(std::mem::replace(&mut self.cells, back));
+        self.generation += 1;
+        changed
+    }
+
+    /// Steps until nothing changes. Returns the number of steps taken,
+    /// counting the final one that changed nothing.
+    #[allow(dead_code)]
+    pub fn until_stable<R>(&mut self, mut rule: R) -> usize
+    where
+        R: FnMut(&S::Item, &[S::Item]) -> S::Item,
+    {
+        let start = self.generation;
+        while self.step(&mut rule) > 0 {}
+        self.generation - start
+    }
+
+    /// Steps until `done` holds for the cells. Returns the number of steps taken.
+    #[allow(dead_code)]
+    pub fn until<R, P>(&mut self, mut rule: R, mut done: P) -> usize
+    where
+        R: FnMut(&S::Item, &[S::Item]) -> S::Item,
+        P: FnMut(&S) -> bool,
+    {
+        let start = self.generation;
+        while !done(&self.cells) {
+            self.step(&mut rule);
+        }
+        self.generation - start
+    }
+
+    /// Event-driven update: every queued cell for which `fires` holds fires
+    /// once, applying `spread` to each neighbour and queueing it in turn.
+    /// Starts from `seeds` and returns the fired cells in firing order.
+    pub fn cascade<F, G>(
+        &mut self,
+        seeds: impl IntoIterator<Item = S::Index>,
+        mut fires: F,
+        mut spread: G,
+    ) -> Vec<S::Index>
+    where
+        F: FnMut(&S::Item) -> bool,
+        G: FnMut(&mut S::Item),
+    {
+        let mut queue = seeds.into_iter().collect::<VecDeque<_>>();
+        let mut fired = HashSet::new();
+        let mut order = vec![];
+        while let Some(i) = queue.pop_front() {
+            if fired.contains(&i) || !self.cells.cell(i).is_some_and(|v| fires(&v)) {
+                continue;
+            }
+            fired.insert(i);
+            order.push(i);
+            for n in self.cells.adjacent(i, &self.neighborhood) {
+                if let Some(mut v) = self.cells.cell(n) {
+                    spread(&mut v);
+                    self.cells.set(n, v);
+                    queue.push_back(n);
+                }
+            }
+        }
+        order
+    }
+}
+
+#[test]
+fn test_life() {
+    let rule = |&alive: &bool, ns: &[bool]| {
+        let n = ns.iter().filter(|&&v| v).count();
+        n == 3 || alive && n == 2
+    };
+    let glider = ".#.\n..#\n###\n";
+
+    let parse = || SparseGrid::parse(glider, false, |c| (c == '#').then_some(true));
+    let mut life = Automaton::new(parse(), Moore);
+    for _ in 0..4 {
+        life.step(rule);
+    }
+    let cells = life.cells().iter().map(|(p, _)| p).collect::<HashSet<_>>();
+    let moved = parse().iter().map(|(p, _)| p + Point::new(1, 1)).collect();
+    assert_eq!(cells, moved);
+
+    let blinker = vec![vec![false, true, false]; 3];
+    let mut life = Automaton::new(blinker.clone(), Moore);
+    assert_eq!(life.until(rule, |cells| cells[1] == [true; 3]), 1);
+    assert_eq!(life.step(rule), 4);
+    assert_eq!(*life.cells(), blinker);
+    assert_eq!(life.generation(), 2);
+
+    let mut block = Automaton::new(vec![vec![true, true], vec![true, true]], Moore);
+    assert_eq!(block.until_stable(rule), 1);
+}
+
+#[test]
+fn test_cascade() {
+    let mut chain = Automaton::new(vec![vec![9, 8, 0, 9]], VonNeumann);
+    let fired = chain.cascade([Coord::new(0, 0)], |&v| v >= 9, |v| *v += 1);
+    assert_eq!(fired, [Coord::new(0, 0), Coord::new(1, 0)]);
+    assert_eq!(*chain.cells(), [vec![10, 9, 1, 9]]);
+
+    use super::matrix::{Boundary, Matrix};
+
+    // On a torus the last cell's right neighbour is the first.
+    let mut ring = Automaton::new(
+        vec![vec![0, 0, 9]].with_boundary(Boundary::Wrap),
+        VonNeumann,
+    );
+    let fired = ring.cascade([Coord::new(2, 0)], |&v| v >= 9, |v| *v += 1);
+    assert_eq!(fired, [Coord::new(2, 0)]);
+    assert_eq!(ring.cells().inner(), &[vec![1, 1, 9]]);
+
+    // Spreading back to the default leaves no cell stored behind.
+    let mut sparse = SparseGrid::new(0);
+    sparse.insert(Point::ORIGIN, 1);
+    let mut drain = Automaton::new(sparse, VonNeumann);
+    drain.cascade([Point::ORIGIN], |&v| v == 1, |v| *v = 0);
+    assert_eq!(drain.cells().len(), 1);
+    assert_eq!(drain.cells().bounds(), Some((Point::ORIGIN, Point::ORIGIN)));
+}
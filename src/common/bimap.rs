@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Index;
+
+use super::reverse::Reverse;
+
+/// One-to-one map with O(1) lookups in both directions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BiMap<K: Eq + Hash, V: Eq + Hash> {
+    forward: HashMap<K, V>,
+    backward: HashMap<V, K>,
+}
+
+impl<K, V> BiMap<K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone + Eq + Hash,
+{
+    pub fn new() -> Self {
+        BiMap {
+            forward: HashMap::new(),
+            backward: HashMap::new(),
+        }
+    }
+
+    /// Maps `k` to `v`, returning the value `k` had before. Fails with the
+    /// other key if `v` already belongs to one, leaving the map unchanged.
+    pub fn insert(&mut self, k: K, v: V) -> Result<Option<V>, K> {
+        if let Some(other) = self.backward.get(&v) {
+            if *other != k {
+                return Err(other.clone());
+            }
+        }
+        let old = self.forward.insert(k.clone(), v.clone());
+        if let Some(old) = old.as_ref() {
+            self.backward.remove(old);
+        }
+        self.backward.insert(v, k);
+        Ok(old)
+    }
+
+    pub fn get(&self, k: &K) -> Option<&V> {
+        self.forward.get(k)
+    }
+
+    pub fn get_key(&self, v: &V) -> Option<&K> {
+        self.backward.get(v)
+    }
+
+    pub fn contains_key(&self, k: &K) -> bool {
+        self.forward.contains_key(k)
+    }
+
+    pub fn contains_value(&self, v: &V) -> bool {
+        self.backward.contains_key(v)
+    }
+
+    pub fn remove(&mut self, k: &K) -> Option<V> {
+        let v = self.forward.remove(k)?;
+        self.backward.remove(&v);
+        Some(v)
+    }
+
+    pub fn remove_value(&mut self, v: &V) -> Option<K> {
+        let k = self.backward.remove(v)?;
+        self.forward.remove(&k);
+        Some(k)
+    }
+
+    pub fn len(&self) -> usize {
+        self.forward.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.forward.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.forward.iter()
+    }
+
+    /// The same pairs looked up from the other side. Free, no rehashing.
+    pub fn inverse(self) -> BiMap<V, K> {
+        BiMap {
+            forward: self.backward,
+            backward: self.forward,
+        }
+    }
+}
+
+impl<K, V> Default for BiMap<K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone + Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> Index<&K> for BiMap<K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone + Eq + Hash,
+{
+    type Output = V;
+
+    fn index(&self, k: &K) -> &V {
+        &self.forward[k]
+    }
+}
+
+/// Fails with the first value found under two keys.
+impl<K, V> TryFrom<HashMap<K, V>> for BiMap<K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone + Eq + Hash,
+{
+    type Error = V;
+
+    fn try_from(map: HashMap<K, V>) -> Result<Self, V> {
+        Ok(BiMap {
+            backward: map.reverse()?,
+            forward: map,
+        })
+    }
+}
+
+#[test]
+fn test_bimap() {
+    let mut m = BiMap::new();
+    assert_eq!(m.insert('a', 1), Ok(None));
+    assert_eq!(m.insert('b', 2), Ok(None));
+    assert_eq!(m.insert('c', 1), Err('a'));
+    assert_eq!(m.insert('a', 3), Ok(Some(1)));
+    assert_eq!(
+        (m.get(&'a'), m.get_key(&3), m.get_key(&1)),
+        (Some(&3), Some(&'a'), None)
+    );
+    assert_eq!(m.remove_value(&2), Some('b'));
+    assert!(!m.contains_key(&'b') && m.len() == 1);
+
+    let inv = m.clone().inverse();
+    assert_eq!((inv[&3], inv.get_key(&'a')), ('a', Some(&3)));
+
+    let ok = BiMap::try_from(HashMap::from([(1, 'x'), (2, 'y')])).unwrap();
+    assert_eq!(ok.get_key(&'y'), Some(&2));
+    assert_eq!(
+        BiMap::try_from(HashMap::from([(1, 'x'), (2, 'x')])),
+        Err('x')
+    );
+}
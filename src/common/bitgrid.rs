@@ -0,0 +1,302 @@
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use super::matrix::{Coord, Matrix};
+
+const BITS: usize = u64::BITS as usize;
+
+/// Grid of bits, each row packed into whole `u64`s. Bits past the width of
+/// a row are always zero.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    width: usize,
+    height: usize,
+    stride: usize,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(BITS);
+        BitGrid {
+            words: vec![0; stride * height],
+            width,
+            height,
+            stride,
+        }
+    }
+
+    pub fn from_matrix<M: Matrix<Item = bool>>(m: &M) -> Self {
+        let mut grid = Self::new(m.width(), m.height());
+        for (c, v) in m.flat_iter() {
+            grid.set(c, v);
+        }
+        grid
+    }
+
+    /// Parses one row per line, with `on` marking set bits.
+    pub fn parse(s: &str, on: char) -> Self {
+        let lines = s.lines().collect::<Vec<_>>();
+        let width = lines.first().map_or(0, |line| line.chars().count());
+        let mut grid = Self::new(width, lines.len());
+        for (y, line) in lines.iter().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                grid.set(Coord::new(x, y), c == on);
+            }
+        }
+        grid
+    }
+
+    pub fn get(&self, c: Coord) -> bool {
+        self.words[self.word(c)] >> (c.x % BITS) & 1 == 1
+    }
+
+    pub fn set(&mut self, c: Coord, v: bool) {
+        let i = self.word(c);
+        let bit = 1 << (c.x % BITS);
+        if v {
+            self.words[i] |= bit;
+        } else {
+            self.words[i] &= !bit;
+        }
+    }
+
+    pub fn toggle(&mut self, c: Coord) {
+        let i = self.word(c);
+        self.words[i] ^= 1 << (c.x % BITS);
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn row_count(&self, y: usize) -> usize {
+        self.row(y).iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn col_count(&self, x: usize) -> usize {
+        (0..self.height)
+            .filter(|&y| self.get(Coord::new(x, y)))
+            .count()
+    }
+
+    pub fn any_full_row(&self) -> bool {
+        let full = self.full_row();
+        self.words.chunks(self.stride.max(1)).any(|row| row == full)
+    }
+
+    pub fn any_full_col(&self) -> bool {
+        if self.height == 0 {
+            return false;
+        }
+        (0..self.stride).any(|i| {
+            (0..self.height)
+                .map(|y| self.words[y * self.stride + i])
+                .fold(!0, |acc, w| acc & w)
+                != 0
+        })
+    }
+
+    /// Moves every bit by `(dx, dy)`. Bits pushed past the edges are lost
+    /// and vacated cells are cleared.
+    pub fn shift(&self, dx: i64, dy: i64) -> Self {
+        let mut out = Self::new(self.width, self.height);
+        for y in 0..self.height {
+            let Some(src) = y
+                .checked_add_signed(-dy as isize)
+                .filter(|&y| y < self.height)
+            else {
+                continue;
+            };
+            let (words, bits) = (
+                dx.unsigned_abs() as usize / BITS,
+                dx.unsigned_abs() as usize % BITS,
+            );
+            let row = self.row(src);
+            let dst = &mut out.words[y * self.stride..(y + 1) * self.stride];
+            for (i, w) in dst.iter_mut().enumerate() {
+                let at = |j: Option<usize>| j.and_then(|j| row.get(j)).copied().unwrap_or(0);
+                *w = if dx >= 0 {
+                    let hi = at(i.checked_sub(words));
+                    let lo = at(i.checked_sub(words + 1));
+                    if bits == 0 {
+                        hi
+                    } else {
+                        hi << bits | lo >> (BITS - bits)
+                    }
+                } else {
+                    let lo = at(Some(i + words));
+                    let hi = at(Some(i + words + 1));
+                    if bits == 0 {
+                        lo
+                    } else {
+                        lo >> bits | hi << (BITS - bits)
+                    }
+                };
+            }
+        }
+        out.mask();
+        out
+    }
+
+    pub fn iter_ones(&self) -> impl Iterator<Item = Coord> + '_ {
+        (0..self.height).flat_map(move |y| {
+            self.row(y).iter().enumerate().flat_map(move |(i, &w)| {
+                let mut w = w;
+                std::iter::from_fn(move || {
+                    (w != 0).then(|| {
+                        let bit = w.trailing_zeros() as usize;
+                        w &= w - 1;
+                        Coord::new(i * BITS + bit, y)
+                    })
+                })
+            })
+        })
+    }
+
+    fn row(&self, y: usize) -> &[u64] {
+        &self.words[y * self.stride..(y + 1) * self.stride]
+    }
+
+    fn word(&self, c: Coord) -> usize {
+        assert!(c.x < self.width && c.y < self.height, "{} out of bounds", c);
+        c.y * self.stride + c.x / BITS
+    }
+
+    /// A row with every bit up to the width set.
+    fn full_row(&self) -> Vec<u64> {
+        let mut row = vec![!0; self.stride];
+        if let Some(last) = row.last_mut() {
+            *last = last_mask(self.width);
+        }
+        row
+    }
+
+    fn mask(&mut self) {
+        let mask = last_mask(self.width);
+        for row in self.words.chunks_mut(self.stride.max(1)) {
+            if let Some(last) = row.last_mut() {
+                *last &= mask;
+            }
+        }
+    }
+
+    fn zip_with(&mut self, other: &BitGrid, f: impl Fn(u64, u64) -> u64) {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "mismatched bit grids"
+        );
+        for (a, &b) in self.words.iter_mut().zip(other.words.iter()) {
+            *a = f(*a, b);
+        }
+    }
+}
+
+fn last_mask(width: usize) -> u64 {
+    match width % BITS {
+        0 => !0,
+        bits => (1 << bits) - 1,
+    }
+}
+
+impl Matrix for BitGrid {
+    type Item = bool;
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn get_coord(&self, c: Coord) -> Option<bool> {
+        (c.x < self.width && c.y < self.height).then(|| self.get(c))
+    }
+
+    fn transpose(&self) -> Self {
+        let mut out = Self::new(self.height, self.width);
+        for c in self.iter_ones() {
+            out.set(Coord::new(c.y, c.x), true);
+        }
+        out
+    }
+}
+
+macro_rules! bit_op {
+    ($Op: ident, $op: ident, $OpAssign: ident, $op_assign: ident, $f: expr) => {
+        impl $OpAssign<&BitGrid> for BitGrid {
+            fn $op_assign(&mut self, rhs: &BitGrid) {
+                self.zip_with(rhs, $f);
+            }
+        }
+
+        impl $Op<&BitGrid> for &BitGrid {
+            type Output = BitGrid;
+
+            fn $op(self, rhs: &BitGrid) -> BitGrid {
+                let mut out = self.clone();
+                out.$op_assign(rhs);
+                out
+            }
+        }
+    };
+}
+
+bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign, |a, b| a & b);
+bit_op!(BitOr, bitor, BitOrAssign, bitor_assign, |a, b| a | b);
+bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, |a, b| a ^ b);
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> BitGrid {
+        let mut out = self.clone();
+        for w in out.words.iter_mut() {
+            *w = !*w;
+        }
+        out.mask();
+        out
+    }
+}
+
+#[test]
+fn test_bitgrid() {
+    let a = BitGrid::parse("#.#\n.##\n..#\n", '#');
+    assert_eq!(a.count_ones(), 5);
+    assert_eq!((a.row_count(1), a.col_count(2)), (2, 3));
+    assert!(!a.any_full_row());
+    assert!(a.any_full_col());
+    assert_eq!((!&a).count_ones(), 4);
+    assert!((&a | &!&a).any_full_row());
+    assert_eq!((&a ^ &a).count_ones(), 0);
+    assert_eq!(&a & &a.transpose(), BitGrid::parse("#..\n.#.\n..#\n", '#'));
+    assert_eq!(a.shift(1, 1), BitGrid::parse("...\n.#.\n..#\n", '#'));
+    assert_eq!(a.shift(-2, -1), BitGrid::parse("#..\n#..\n...\n", '#'));
+    assert_eq!(
+        a.iter_ones().collect::<Vec<_>>(),
+        [(0, 0), (2, 0), (1, 1), (2, 1), (2, 2)].map(|(x, y)| Coord::new(x, y))
+    );
+}
+
+#[test]
+fn test_bitgrid_wide() {
+    let mut a = BitGrid::new(130, 2);
+    for x in 0..130 {
+        a.set(Coord::new(x, 0), true);
+    }
+    assert!(a.any_full_row());
+    assert_eq!(a.row_count(0), 130);
+    let shifted = a.shift(70, 1);
+    assert_eq!(shifted.row_count(1), 60);
+    assert!(shifted.get(Coord::new(70, 1)) && !shifted.get(Coord::new(69, 1)));
+    let back = shifted.shift(-65, 0);
+    assert_eq!(back.row_count(1), 60);
+    assert!(back.get(Coord::new(5, 1)) && back.get(Coord::new(64, 1)));
+    assert!(!back.get(Coord::new(65, 1)));
+    assert_eq!(a.transpose().col_count(0), 130);
+}
@@ -1,33 +1,438 @@
-use std::{collections::HashMap, hash::Hash};
+use std::{
+    array,
+    cmp::Reverse,
+    collections::{btree_map, hash_map, BTreeMap, HashMap},
+    hash::Hash,
+    iter::{Enumerate, FilterMap},
+    marker::PhantomData,
+    ops::{Add, BitAnd, BitOr, Sub},
+};
+
+/// Storage behind a `Counter`. Keys are only stored while their count is
+/// nonzero.
+pub trait Counts<T>: Default {
+    /// What `iter` yields keys as: borrowed from a map, copied out of
+    /// `Dense`.
+    type Key<'a>
+    where
+        Self: 'a;
+    type IntoIter: Iterator<Item = (T, usize)>;
+
+    fn get(&self, k: &T) -> usize;
+    fn add(&mut self, k: T, n: usize);
+    /// Lowers the count of `k` by up to `n`, returning the old count.
+    fn remove(&mut self, k: &T, n: usize) -> usize;
+    /// Number of keys with a nonzero count.
+    #[allow(dead_code)]
+    fn len(&self) -> usize;
+    fn iter(&self) -> impl Iterator<Item = (Self::Key<'_>, usize)> + '_;
+    fn into_counts(self) -> Self::IntoIter;
+}
+
+impl<T: Eq + Hash> Counts<T> for HashMap<T, usize> {
+    type Key<'a>
+        = &'a T
+    where
+        T: 'a;
+    type IntoIter = hash_map::IntoIter<T, usize>;
+
+    fn get(&self, k: &T) -> usize {
+        HashMap::get(self, k).copied().unwrap_or(0)
+    }
+
+    fn add(&mut self, k: T, n: usize) {
+        if n > 0 {
+            *self.entry(k).or_insert(0) += n;
+        }
+    }
+
+    fn remove(&mut self, k: &T, n: usize) -> usize {
+        match self.get_mut(k) {
+            Some(c) if *c > n => std::mem::replace(c, *c - n),
+            Some(_) => HashMap::remove(self, k).unwrap(),
+            None => 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&T, usize)> + '_ {
+        HashMap::iter(self).map(|(k, &v)| (k, v))
+    }
+
+    fn into_counts(self) -> Self::IntoIter {
+        self.into_iter()
+    }
+}
+
+impl<T: Ord> Counts<T> for BTreeMap<T, usize> {
+    type Key<'a>
+        = &'a T
+    where
+        T: 'a;
+    type IntoIter = btree_map::IntoIter<T, usize>;
+
+    fn get(&self, k: &T) -> usize {
+        BTreeMap::get(self, k).copied().unwrap_or(0)
+    }
+
+    fn add(&mut self, k: T, n: usize) {
+        if n > 0 {
+            *self.entry(k).or_insert(0) += n;
+        }
+    }
+
+    fn remove(&mut self, k: &T, n: usize) -> usize {
+        match self.get_mut(k) {
+            Some(c) if *c > n => std::mem::replace(c, *c - n),
+            Some(_) => BTreeMap::remove(self, k).unwrap(),
+            None => 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&T, usize)> + '_ {
+        BTreeMap::iter(self).map(|(k, &v)| (k, v))
+    }
+
+    fn into_counts(self) -> Self::IntoIter {
+        self.into_iter()
+    }
+}
+
+/// Counts of the small integers `0..N`, one slot each.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Dense<const N: usize>([usize; N]);
+
+impl<const N: usize> Default for Dense<N> {
+    fn default() -> Self {
+        Dense([0; N])
+    }
+}
+
+type DenseIter<const N: usize> =
+    FilterMap<Enumerate<array::IntoIter<usize, N>>, fn((usize, usize)) -> Option<(usize, usize)>>;
+
+impl<const N: usize> Counts<usize> for Dense<N> {
+    type Key<'a> = usize;
+    type IntoIter = DenseIter<N>;
+
+    fn get(&self, &k: &usize) -> usize {
+        self.0.get(k).copied().unwrap_or(0)
+    }
+
+    fn add(&mut self, k: usize, n: usize) {
+        assert!(k < N, "key {} out of range for {} slots", k, N);
+        self.0[k] += n;
+    }
+
+    fn remove(&mut self, &k: &usize, n: usize) -> usize {
+        self.0.get_mut(k).map_or(0, |c| {
+            let old = *c;
+            *c = c.saturating_sub(n);
+            old
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.0.iter().filter(|&&v| v > 0).count()
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.0.iter().copied().enumerate().filter(|&(_, v)| v > 0)
+    }
+
+    fn into_counts(self) -> Self::IntoIter {
+        self.0
+            .into_iter()
+            .enumerate()
+            .filter_map(|(k, v)| (v > 0).then_some((k, v)))
+    }
+}
+
+/// Multiset of `T`, counting how often each key occurs. Hash-backed unless
+/// another `Counts` is picked, e.g. through `OrderedCounter` or
+/// `DenseCounter`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Counter<T, B = HashMap<T, usize>> {
+    counts: B,
+    keys: PhantomData<T>,
+}
+
+/// Iterates in key order, so output is stable between runs.
+#[allow(dead_code)]
+pub type OrderedCounter<T> = Counter<T, BTreeMap<T, usize>>;
+
+/// Array-backed counter for keys in `0..N`, iterating in key order.
+pub type DenseCounter<const N: usize> = Counter<usize, Dense<N>>;
+
+impl<T: Eq + Hash> Counter<T> {
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T: Ord> OrderedCounter<T> {
+    #[allow(dead_code)]
+    pub fn ordered() -> Self {
+        Self::default()
+    }
+}
+
+impl<const N: usize> DenseCounter<N> {
+    #[allow(dead_code)]
+    pub fn dense() -> Self {
+        Self::default()
+    }
+}
+
+impl<T, B: Counts<T>> Counter<T, B> {
+    /// Sums the counts of repeated keys.
+    #[allow(dead_code)]
+    pub fn from_counts(iter: impl IntoIterator<Item = (T, usize)>) -> Self {
+        let mut count = Self::default();
+        for (k, v) in iter {
+            count.insert(k, v);
+        }
+        count
+    }
+
+    pub fn get(&self, k: &T) -> usize {
+        self.counts.get(k)
+    }
+
+    pub fn insert(&mut self, k: T, n: usize) {
+        self.counts.add(k, n);
+    }
+
+    /// Lowers the count of `k` by up to `n`, returning the old count.
+    pub fn remove(&mut self, k: &T, n: usize) -> usize {
+        self.counts.remove(k, n)
+    }
+
+    /// Number of distinct keys.
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sum of all counts.
+    #[allow(dead_code)]
+    pub fn total(&self) -> usize {
+        self.values().sum()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (B::Key<'_>, usize)> + '_ {
+        self.counts.iter()
+    }
+
+    #[allow(dead_code)]
+    pub fn keys(&self) -> impl Iterator<Item = B::Key<'_>> + '_ {
+        self.iter().map(|(k, _)| k)
+    }
+
+    #[allow(dead_code)]
+    pub fn values(&self) -> impl Iterator<Item = usize> + '_ {
+        self.iter().map(|(_, v)| v)
+    }
+
+    /// Keys occurring at least `n` times.
+    pub fn at_least(&self, n: usize) -> impl Iterator<Item = (B::Key<'_>, usize)> + '_ {
+        self.iter().filter(move |&(_, v)| v >= n)
+    }
+
+    /// The `n` most frequent keys, most frequent first. Ties keep iteration
+    /// order.
+    #[allow(dead_code)]
+    pub fn most_common(&self, n: usize) -> Vec<(B::Key<'_>, usize)> {
+        let mut all = self.iter().collect::<Vec<_>>();
+        all.sort_by_key(|&(_, v)| Reverse(v));
+        all.truncate(n);
+        all
+    }
+
+    /// The `n` least frequent keys, least frequent first.
+    #[allow(dead_code)]
+    pub fn least_common(&self, n: usize) -> Vec<(B::Key<'_>, usize)> {
+        let mut all = self.iter().collect::<Vec<_>>();
+        all.sort_by_key(|&(_, v)| v);
+        all.truncate(n);
+        all
+    }
+
+    fn zip_with(self, mut rhs: Self, f: impl Fn(usize, usize) -> usize) -> Self {
+        let mut out = Self::default();
+        for (k, v) in self {
+            // Taking the key out of `rhs` leaves only its keys missing here.
+            let w = rhs.remove(&k, usize::MAX);
+            out.insert(k, f(v, w));
+        }
+        for (k, w) in rhs {
+            out.insert(k, f(0, w));
+        }
+        out
+    }
+}
+
+impl<T, B: Default> Default for Counter<T, B> {
+    fn default() -> Self {
+        Counter {
+            counts: B::default(),
+            keys: PhantomData,
+        }
+    }
+}
+
+impl<T, B: Counts<T>> FromIterator<T> for Counter<T, B> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut count = Self::default();
+        count.extend(iter);
+        count
+    }
+}
+
+impl<T, B: Counts<T>> Extend<T> for Counter<T, B> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for k in iter {
+            self.insert(k, 1);
+        }
+    }
+}
+
+impl<T, B: Counts<T>> IntoIterator for Counter<T, B> {
+    type Item = (T, usize);
+    type IntoIter = B::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.into_counts()
+    }
+}
+
+impl<T, B: Counts<T>> Add for Counter<T, B> {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        for (k, v) in rhs {
+            self.insert(k, v);
+        }
+        self
+    }
+}
+
+/// Saturating: keys whose count would drop to zero or below are removed.
+impl<T, B: Counts<T>> Sub for Counter<T, B> {
+    type Output = Self;
+
+    fn sub(mut self, rhs: Self) -> Self {
+        for (k, v) in rhs {
+            self.remove(&k, v);
+        }
+        self
+    }
+}
+
+/// Union, keeping the larger count of each key.
+impl<T, B: Counts<T>> BitOr for Counter<T, B> {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.zip_with(rhs, usize::max)
+    }
+}
+
+/// Intersection, keeping the smaller count of each key.
+impl<T, B: Counts<T>> BitAnd for Counter<T, B> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        self.zip_with(rhs, usize::min)
+    }
+}
 
 pub trait Countable {
-    type Item;
-    fn counter(&mut self) -> HashMap<Self::Item, usize>;
+    type Item: Eq + Hash;
+    fn counter(&mut self) -> Counter<Self::Item>;
 }
 
 impl<T: Eq + Hash, I: Iterator<Item = T>> Countable for I {
     type Item = T;
-    fn counter(&mut self) -> HashMap<T, usize> {
-        let mut count = HashMap::new();
-        for k in self {
-            count.entry(k).and_modify(|v| *v += 1).or_insert(1);
-        }
-        count
+    fn counter(&mut self) -> Counter<T> {
+        self.collect()
     }
 }
 
+#[allow(dead_code)]
 pub trait CounterSum {
-    type Item;
-    fn counter_sum(&mut self) -> HashMap<Self::Item, usize>;
+    type Item: Eq + Hash;
+    fn counter_sum(&mut self) -> Counter<Self::Item>;
 }
 
 impl<T: Eq + Hash, I: Iterator<Item = (T, usize)>> CounterSum for I {
     type Item = T;
-    fn counter_sum(&mut self) -> HashMap<T, usize> {
-        let mut count = HashMap::new();
-        for (k, v) in self {
-            count.entry(k).and_modify(|ov| *ov += v).or_insert(v);
-        }
-        count
+    fn counter_sum(&mut self) -> Counter<T> {
+        Counter::from_counts(self)
     }
 }
+
+#[test]
+fn test_counter() {
+    let a = "abracadabra".chars().counter();
+    assert_eq!(
+        (a.get(&'a'), a.get(&'z'), a.len(), a.total()),
+        (5, 0, 5, 11)
+    );
+    assert_eq!(a.most_common(1), [(&'a', 5)]);
+    assert_eq!(
+        a.most_common(3).iter().map(|&(_, v)| v).collect::<Vec<_>>(),
+        [5, 2, 2]
+    );
+    assert_eq!(a.least_common(1).len(), 1);
+    assert_eq!(a.least_common(2)[0].1, 1);
+
+    let b = "barbara".chars().counter();
+    let sum = a.clone() + b.clone();
+    assert_eq!((sum.get(&'a'), sum.get(&'r'), sum.total()), (8, 4, 18));
+    let diff = a.clone() - b.clone();
+    assert_eq!((diff.get(&'a'), diff.get(&'r'), diff.get(&'c')), (2, 0, 1));
+    assert!(!diff.keys().any(|&k| k == 'r'));
+    assert_eq!((a.clone() | b.clone()).total(), 5 + 2 + 2 + 1 + 1);
+    assert_eq!((a & b).total(), 3 + 2 + 2);
+    assert_eq!(sum.at_least(4).count(), 3);
+}
+
+#[test]
+fn test_ordered_counters() {
+    let a = "abracadabra".chars().collect::<OrderedCounter<_>>();
+    assert_eq!(
+        a.iter().collect::<Vec<_>>(),
+        [(&'a', 5), (&'b', 2), (&'c', 1), (&'d', 1), (&'r', 2)]
+    );
+    assert_eq!(a.most_common(3), [(&'a', 5), (&'b', 2), (&'r', 2)]);
+    assert_eq!(a.least_common(2), [(&'c', 1), (&'d', 1)]);
+
+    let mut fish = [3, 4, 3, 1, 2].into_iter().collect::<DenseCounter<9>>();
+    assert_eq!(
+        fish.iter().collect::<Vec<_>>(),
+        [(1, 1), (2, 1), (3, 2), (4, 1)]
+    );
+    assert_eq!(fish.remove(&3, 5), 2);
+    assert_eq!((fish.get(&3), fish.get(&20), fish.len()), (0, 0, 3));
+    let both = fish.clone() | DenseCounter::from_counts([(8, 2), (1, 3)]);
+    assert_eq!(
+        both.into_iter().collect::<Vec<_>>(),
+        [(1, 3), (2, 1), (4, 1), (8, 2)]
+    );
+    assert_eq!(DenseCounter::<4>::dense().total(), 0);
+    assert!(OrderedCounter::<u8>::ordered().is_empty());
+}
@@ -0,0 +1,281 @@
+use std::ops::ControlFlow;
+
+/// Set of candidate values `0..64` for one variable.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Domain(u64);
+
+impl Domain {
+    /// Every value in `0..n`.
+    pub fn full(n: usize) -> Self {
+        assert!(n <= 64, "domains hold at most 64 values, not {}", n);
+        Domain(if n == 64 { !0 } else { (1 << n) - 1 })
+    }
+
+    pub fn only(v: usize) -> Self {
+        Domain(1 << v)
+    }
+
+    pub fn contains(self, v: usize) -> bool {
+        v < 64 && self.0 >> v & 1 == 1
+    }
+
+    pub fn insert(&mut self, v: usize) {
+        assert!(v < 64, "domains hold values below 64, not {}", v);
+        self.0 |= 1 << v;
+    }
+
+    pub fn remove(&mut self, v: usize) {
+        if v < 64 {
+            self.0 &= !(1 << v);
+        }
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The value, if exactly one is left.
+    pub fn single(self) -> Option<usize> {
+        (self.len() == 1).then(|| self.0.trailing_zeros() as usize)
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = usize> {
+        let mut w = self.0;
+        std::iter::from_fn(move || {
+            (w != 0).then(|| {
+                let v = w.trailing_zeros() as usize;
+                w &= w - 1;
+                v
+            })
+        })
+    }
+}
+
+type Predicate<'a> = Box<dyn Fn(&[usize]) -> bool + 'a>;
+
+enum Constraint<'a> {
+    AllDifferent(Vec<usize>),
+    /// Forward checked: a value stays only while some choice of the other
+    /// variables' values satisfies the predicate. That tries every
+    /// combination of their domains, so narrow them first.
+    Holds(Vec<usize>, Predicate<'a>),
+}
+
+/// Finite-domain constraint solver: propagation to a fixpoint, then
+/// backtracking on the variable with the fewest candidates left.
+pub struct Solver<'a> {
+    domains: Vec<Domain>,
+    constraints: Vec<Constraint<'a>>,
+}
+
+impl<'a> Solver<'a> {
+    /// `vars` variables, each taking a value in `0..values`.
+    pub fn new(vars: usize, values: usize) -> Self {
+        Solver {
+            domains: vec![Domain::full(values); vars],
+            constraints: vec![],
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn domain(&self, var: usize) -> Domain {
+        self.domains[var]
+    }
+
+    /// Keeps only the values of `var` that pass `allowed`.
+    pub fn restrict(&mut self, var: usize, allowed: impl Fn(usize) -> bool) {
+        for v in self.domains[var].iter() {
+            if !allowed(v) {
+                self.domains[var].remove(v);
+            }
+        }
+    }
+
+    pub fn all_different(&mut self, vars: impl IntoIterator<Item = usize>) {
+        self.constraints
+            .push(Constraint::AllDifferent(vars.into_iter().collect()));
+    }
+
+    /// Requires `holds` of the values of `vars`, passed in the same order.
+    pub fn constraint<F>(&mut self, vars: impl IntoIterator<Item = usize>, holds: F)
+    where
+        F: Fn(&[usize]) -> bool + 'a,
+    {
+        self.constraints.push(Constraint::Holds(
+            vars.into_iter().collect(),
+            Box::new(holds),
+        ));
+    }
+
+    /// Some assignment satisfying every constraint.
+    pub fn solve(&self) -> Option<Vec<usize>> {
+        self.search(|values| ControlFlow::Break(values.to_vec()))
+    }
+
+    #[allow(dead_code)]
+    pub fn solutions(&self) -> Vec<Vec<usize>> {
+        let mut all = vec![];
+        self.search::<()>(|values| {
+            all.push(values.to_vec());
+            ControlFlow::Continue(())
+        });
+        all
+    }
+
+    /// Calls `visit` with each solution until it breaks.
+    pub fn search<B>(&self, mut visit: impl FnMut(&[usize]) -> ControlFlow<B>) -> Option<B> {
+        match self.branch(self.domains.clone(), &mut visit) {
+            ControlFlow::Break(b) => Some(b),
+            ControlFlow::Continue(()) => None,
+        }
+    }
+
+    fn branch<B>(
+        &self,
+        mut domains: Vec<Domain>,
+        visit: &mut impl FnMut(&[usize]) -> ControlFlow<B>,
+    ) -> ControlFlow<B> {
+        if !self.propagate(&mut domains) {
+            return ControlFlow::Continue(());
+        }
+        let open = (0..domains.len())
+            .filter(|&i| domains[i].len() > 1)
+            .min_by_key(|&i| domains[i].len());
+        let Some(var) = open else {
+            let values = domains
+                .iter()
+                .map(|d| d.single().unwrap())
+                .collect::<Vec<_>>();
+            return visit(&values);
+        };
+        for v in domains[var].iter() {
+            let mut next = domains.clone();
+            next[var] = Domain::only(v);
+            self.branch(next, visit)?;
+        }
+        ControlFlow::Continue(())
+    }
+
+    /// Narrows `domains` until nothing changes. False on a contradiction.
+    fn propagate(&self, domains: &mut [Domain]) -> bool {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for c in self.constraints.iter() {
+                changed |= match c {
+                    Constraint::AllDifferent(vars) => distinct(vars, domains),
+                    Constraint::Holds(vars, holds) => supported(vars, holds, domains),
+                };
+                if domains.iter().any(|d| d.is_empty()) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Removes fixed values from the other domains. True if any shrank.
+fn distinct(vars: &[usize], domains: &mut [Domain]) -> bool {
+    let mut changed = false;
+    for &i in vars.iter() {
+        let Some(v) = domains[i].single() else {
+            continue;
+        };
+        for &j in vars.iter().filter(|&&j| j != i) {
+            changed |= domains[j].contains(v);
+            domains[j].remove(v);
+        }
+    }
+    changed
+}
+
+/// Keeps only the values that appear in some assignment of `vars`
+/// satisfying `holds`. True if any domain shrank.
+fn supported(vars: &[usize], holds: &Predicate, domains: &mut [Domain]) -> bool {
+    let mut values = vec![0; vars.len()];
+    let mut support = vec![Domain::default(); vars.len()];
+    find_support(0, vars, holds, domains, &mut values, &mut support);
+    let mut changed = false;
+    for (&i, s) in vars.iter().zip(support) {
+        let kept = Domain(domains[i].0 & s.0);
+        changed |= kept != domains[i];
+        domains[i] = kept;
+    }
+    changed
+}
+
+fn find_support(
+    slot: usize,
+    vars: &[usize],
+    holds: &Predicate,
+    domains: &[Domain],
+    values: &mut [usize],
+    support: &mut [Domain],
+) {
+    if slot == vars.len() {
+        if holds(values) {
+            for (s, &v) in support.iter_mut().zip(values.iter()) {
+                s.insert(v);
+            }
+        }
+        return;
+    }
+    for v in domains[vars[slot]].iter() {
+        values[slot] = v;
+        find_support(slot + 1, vars, holds, domains, values, support);
+    }
+}
+
+#[test]
+fn test_domain() {
+    let mut d = Domain::full(5);
+    d.remove(1);
+    d.remove(70);
+    d.insert(2);
+    assert_eq!(d.iter().collect::<Vec<_>>(), [0, 2, 3, 4]);
+    assert!(d.contains(4) && !d.contains(1) && !d.contains(5));
+    assert_eq!(
+        (d.len(), d.single(), Domain::only(3).single()),
+        (4, None, Some(3))
+    );
+    assert_eq!(Domain::full(64).len(), 64);
+}
+
+#[test]
+fn test_solver() {
+    // TO + GO = OUT.
+    let mut puzzle = Solver::new(4, 10);
+    puzzle.all_different(0..4);
+    let [t, o, g, u] = [0, 1, 2, 3];
+    for first in [t, g, o] {
+        puzzle.restrict(first, |v| v != 0);
+    }
+    puzzle.constraint([t, o, g, u], move |x| {
+        let num = |ds: &[usize]| ds.iter().fold(0, |acc, &i| acc * 10 + x[i]);
+        num(&[t, o]) + num(&[g, o]) == num(&[o, u, t])
+    });
+    assert_eq!(puzzle.solve(), Some(vec![2, 1, 8, 0]));
+    assert_eq!(puzzle.solutions().len(), 1);
+
+    // Three people in three houses: only the pigeonhole orderings survive.
+    let mut houses = Solver::new(3, 3);
+    houses.all_different(0..3);
+    houses.constraint([0, 1], |x| x[0] < x[1]);
+    assert_eq!(houses.solutions().len(), 3);
+    houses.restrict(2, |v| v == 0);
+    assert_eq!(houses.solve(), Some(vec![1, 2, 0]));
+    houses.constraint([2], |_| false);
+    assert_eq!(houses.solve(), None);
+
+    // Forward checking narrows both sides before anything is chosen.
+    let mut sum = Solver::new(2, 10);
+    sum.constraint([0, 1], |x| x[0] + x[1] == 15);
+    let mut domains = sum.domains.clone();
+    assert!(sum.propagate(&mut domains));
+    assert_eq!(domains[0].iter().collect::<Vec<_>>(), [6, 7, 8, 9]);
+}
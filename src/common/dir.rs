@@ -0,0 +1,146 @@
+use std::ops::Add;
+
+use super::matrix::Coord;
+use super::point::Point;
+
+/// Orthogonal directions, clockwise from `Up`. `Up` is towards row 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dir4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+/// Compass directions, clockwise from `N`. `N` is towards row 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dir8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Dir4 {
+    pub const ALL: [Dir4; 4] = [Dir4::Up, Dir4::Right, Dir4::Down, Dir4::Left];
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    pub fn delta(self) -> Point {
+        match self {
+            Dir4::Up => Point::new(0, -1),
+            Dir4::Right => Point::new(1, 0),
+            Dir4::Down => Point::new(0, 1),
+            Dir4::Left => Point::new(-1, 0),
+        }
+    }
+}
+
+impl Dir8 {
+    pub const ALL: [Dir8; 8] = [
+        Dir8::N,
+        Dir8::NE,
+        Dir8::E,
+        Dir8::SE,
+        Dir8::S,
+        Dir8::SW,
+        Dir8::W,
+        Dir8::NW,
+    ];
+
+    /// Rotates 45° clockwise.
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// Rotates 45° counter-clockwise.
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    pub fn delta(self) -> Point {
+        match self {
+            Dir8::N => Point::new(0, -1),
+            Dir8::NE => Point::new(1, -1),
+            Dir8::E => Point::new(1, 0),
+            Dir8::SE => Point::new(1, 1),
+            Dir8::S => Point::new(0, 1),
+            Dir8::SW => Point::new(-1, 1),
+            Dir8::W => Point::new(-1, 0),
+            Dir8::NW => Point::new(-1, -1),
+        }
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(d: Dir4) -> Self {
+        Dir8::ALL[d as usize * 2]
+    }
+}
+
+impl Add<Dir4> for Point {
+    type Output = Point;
+
+    fn add(self, d: Dir4) -> Point {
+        self + d.delta()
+    }
+}
+
+impl Add<Dir8> for Point {
+    type Output = Point;
+
+    fn add(self, d: Dir8) -> Point {
+        self + d.delta()
+    }
+}
+
+/// `None` when the step would leave the non-negative quadrant.
+impl Add<Dir4> for Coord {
+    type Output = Option<Coord>;
+
+    fn add(self, d: Dir4) -> Option<Coord> {
+        Coord::try_from(Point::from(self) + d).ok()
+    }
+}
+
+impl Add<Dir8> for Coord {
+    type Output = Option<Coord>;
+
+    fn add(self, d: Dir8) -> Option<Coord> {
+        Coord::try_from(Point::from(self) + d).ok()
+    }
+}
+
+#[test]
+fn test_dir() {
+    assert_eq!(Dir4::Up.turn_left(), Dir4::Left);
+    assert_eq!(Dir4::Left.turn_right(), Dir4::Up);
+    assert_eq!(Dir4::Right.opposite(), Dir4::Left);
+    assert_eq!(Dir8::N.turn_left(), Dir8::NW);
+    assert_eq!(Dir8::SW.opposite(), Dir8::NE);
+    assert_eq!(Dir8::from(Dir4::Down), Dir8::S);
+    assert_eq!(Coord::new(0, 1) + Dir4::Up, Some(Coord::new(0, 0)));
+    assert_eq!(Coord::new(0, 1) + Dir8::W, None);
+    assert_eq!(Point::new(0, 0) + Dir8::NW, Point::new(-1, -1));
+    for d in Dir8::ALL {
+        assert_eq!(d.delta() + d.opposite().delta(), Point::ORIGIN);
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use super::matrix::{Coord, Matrix};
+
+/// Disjoint sets over `0..len`, with path compression and union by rank.
+#[derive(Clone, Debug)]
+pub struct Dsu {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    size: Vec<usize>,
+    count: usize,
+}
+
+impl Dsu {
+    pub fn new(len: usize) -> Self {
+        Dsu {
+            parent: (0..len).collect(),
+            rank: vec![0; len],
+            size: vec![1; len],
+            count: len,
+        }
+    }
+
+    /// One element per cell, numbered `y * width + x`, with neighbouring
+    /// cells joined whenever `connected` holds for their values.
+    pub fn from_matrix<M, F>(m: &M, connected: F, diagonals: bool) -> Self
+    where
+        M: Matrix,
+        F: Fn(M::Item, M::Item) -> bool,
+    {
+        let index = |c: Coord| c.y * m.width() + c.x;
+        let mut dsu = Self::new(m.size());
+        for (c, v) in m.flat_iter() {
+            for (n, w) in m.neighbors(c, diagonals) {
+                if connected(v, w) {
+                    dsu.union(index(c), index(n));
+                }
+            }
+        }
+        dsu
+    }
+
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut x = x;
+        while self.parent[x] != root {
+            x = std::mem::replace(&mut self.parent[x], root);
+        }
+        root
+    }
+
+    /// Merges the sets of `a` and `b`. False if they were already one.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (big, small) = if self.rank[a] < self.rank[b] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        if self.rank[a] == self.rank[b] {
+            self.rank[big] += 1;
+        }
+        self.count -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Size of the set containing `x`.
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Number of disjoint sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Members of every set, ordered by their smallest element.
+    pub fn components(&mut self) -> impl Iterator<Item = Vec<usize>> {
+        let mut slots = HashMap::new();
+        let mut sets = vec![];
+        for x in 0..self.len() {
+            let root = self.find(x);
+            let slot = *slots.entry(root).or_insert_with(|| {
+                sets.push(vec![]);
+                sets.len() - 1
+            });
+            sets[slot].push(x);
+        }
+        sets.into_iter()
+    }
+}
+
+#[test]
+fn test_dsu() {
+    let mut dsu = Dsu::new(6);
+    assert!(dsu.union(0, 1));
+    assert!(dsu.union(3, 4));
+    assert!(dsu.union(1, 4));
+    assert!(!dsu.union(0, 3));
+    assert!(dsu.same(0, 4) && !dsu.same(0, 2));
+    assert_eq!((dsu.size(3), dsu.size(5), dsu.count()), (4, 1, 3));
+    assert_eq!(
+        dsu.components().collect::<Vec<_>>(),
+        [vec![0, 1, 3, 4], vec![2], vec![5]]
+    );
+}
+
+#[test]
+fn test_basins() {
+    let heights = "2199943210\n3987894921\n9856789892\n8767896789\n9899965678\n";
+    let grid = super::grid::Grid::parse(heights, |c| c.to_digit(10).unwrap());
+    let mut dsu = Dsu::from_matrix(&grid, |a, b| a != 9 && b != 9, false);
+    let mut sizes = dsu
+        .components()
+        .filter(|set| grid.flat_iter().nth(set[0]).unwrap().1 != 9)
+        .map(|set| set.len())
+        .collect::<Vec<_>>();
+    sizes.sort_by(|a, b| b.cmp(a));
+    assert_eq!(sizes, [14, 9, 9, 3]);
+}
@@ -0,0 +1,259 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use super::walk::Walk;
+
+/// Adjacency-list graph over string-named nodes, each interned to an index
+/// in insertion order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Graph<'a> {
+    names: Vec<&'a str>,
+    ids: HashMap<&'a str, usize>,
+    adj: Vec<Vec<usize>>,
+}
+
+impl<'a> Graph<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses one edge per line: `a -> b` is directed, `a-b` goes both ways.
+    pub fn parse(s: &'a str) -> Self {
+        let mut graph = Self::new();
+        for line in s.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            if let Some((a, b)) = line.split_once("->") {
+                graph.add_directed(a.trim(), b.trim());
+            } else {
+                let (a, b) = line.split_once('-').expect("edge line without '-'");
+                graph.add_edge(a.trim(), b.trim());
+            }
+        }
+        graph
+    }
+
+    /// Index of `name`, adding it if it's new.
+    pub fn add_node(&mut self, name: &'a str) -> usize {
+        *self.ids.entry(name).or_insert_with(|| {
+            self.names.push(name);
+            self.adj.push(vec![]);
+            self.names.len() - 1
+        })
+    }
+
+    pub fn add_directed(&mut self, from: &'a str, to: &'a str) {
+        let (a, b) = (self.add_node(from), self.add_node(to));
+        self.adj[a].push(b);
+    }
+
+    pub fn add_edge(&mut self, a: &'a str, b: &'a str) {
+        self.add_directed(a, b);
+        self.add_directed(b, a);
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: usize) -> &'a str {
+        self.names[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn neighbors(&self, id: usize) -> &[usize] {
+        &self.adj[id]
+    }
+
+    /// Nodes ordered so every edge points forward, always taking the lowest
+    /// ready index next. `None` if there is a cycle.
+    pub fn topo_sort(&self) -> Option<Vec<usize>> {
+        let mut indegree = vec![0; self.len()];
+        for &b in self.adj.iter().flatten() {
+            indegree[b] += 1;
+        }
+        let mut ready = (0..self.len())
+            .filter(|&i| indegree[i] == 0)
+            .map(Reverse)
+            .collect::<BinaryHeap<_>>();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(Reverse(a)) = ready.pop() {
+            order.push(a);
+            for &b in self.adj[a].iter() {
+                indegree[b] -= 1;
+                if indegree[b] == 0 {
+                    ready.push(Reverse(b));
+                }
+            }
+        }
+        (order.len() == self.len()).then_some(order)
+    }
+
+    /// Strongly connected components (Tarjan), in reverse topological order
+    /// of the condensed graph.
+    pub fn sccs(&self) -> Vec<Vec<usize>> {
+        let mut tarjan = Tarjan {
+            graph: self,
+            index: vec![None; self.len()],
+            low: vec![0; self.len()],
+            on_stack: vec![false; self.len()],
+            stack: vec![],
+            next: 0,
+            sccs: vec![],
+        };
+        for v in 0..self.len() {
+            if tarjan.index[v].is_none() {
+                tarjan.visit(v);
+            }
+        }
+        tarjan.sccs
+    }
+
+    /// Every path from `from` to `to` that enters each node `n` at most
+    /// `limit(n)` times. The path ends as soon as it reaches `to`.
+    pub fn paths<L>(&self, from: usize, to: usize, limit: L) -> Vec<Vec<usize>>
+    where
+        L: Fn(usize) -> usize,
+    {
+        let mut visits = vec![0; self.len()];
+        let mut path = vec![];
+        let mut paths = vec![];
+        self.extend_paths(from, to, &limit, &mut visits, &mut path, &mut paths);
+        paths
+    }
+
+    fn extend_paths<L>(
+        &self,
+        cur: usize,
+        to: usize,
+        limit: &L,
+        visits: &mut [usize],
+        path: &mut Vec<usize>,
+        paths: &mut Vec<Vec<usize>>,
+    ) where
+        L: Fn(usize) -> usize,
+    {
+        if visits[cur] >= limit(cur) {
+            return;
+        }
+        visits[cur] += 1;
+        path.push(cur);
+        if cur == to {
+            paths.push(path.clone());
+        } else {
+            for &next in self.adj[cur].iter() {
+                self.extend_paths(next, to, limit, visits, path, paths);
+            }
+        }
+        path.pop();
+        visits[cur] -= 1;
+    }
+}
+
+struct Tarjan<'g, 'a> {
+    graph: &'g Graph<'a>,
+    index: Vec<Option<usize>>,
+    low: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    next: usize,
+    sccs: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_, '_> {
+    fn visit(&mut self, v: usize) {
+        self.index[v] = Some(self.next);
+        self.low[v] = self.next;
+        self.next += 1;
+        self.stack.push(v);
+        self.on_stack[v] = true;
+        for &w in self.graph.adj[v].iter() {
+            match self.index[w] {
+                None => {
+                    self.visit(w);
+                    self.low[v] = self.low[v].min(self.low[w]);
+                }
+                Some(i) if self.on_stack[w] => self.low[v] = self.low[v].min(i),
+                Some(_) => {}
+            }
+        }
+        if Some(self.low[v]) == self.index[v] {
+            let mut scc = vec![];
+            while let Some(w) = self.stack.pop() {
+                self.on_stack[w] = false;
+                scc.push(w);
+                if w == v {
+                    break;
+                }
+            }
+            self.sccs.push(scc);
+        }
+    }
+}
+
+impl<'a> Walk for Graph<'a> {
+    type Item = &'a str;
+    type Index = usize;
+
+    fn value(&self, id: usize) -> &'a str {
+        self.names[id]
+    }
+
+    fn size_hint(&self) -> usize {
+        self.len()
+    }
+}
+
+#[test]
+fn test_caves() {
+    let caves = Graph::parse("start-A\nstart-b\nA-c\nA-b\nb-d\nA-end\nb-end\n");
+    let (start, end) = (caves.id("start").unwrap(), caves.id("end").unwrap());
+    let small_once = |n| {
+        if caves.name(n).chars().all(char::is_uppercase) {
+            usize::MAX
+        } else {
+            1
+        }
+    };
+    let paths = caves.paths(start, end, small_once);
+    assert_eq!(paths.len(), 10);
+    assert!(paths.iter().all(|p| p[0] == start && p[p.len() - 1] == end));
+
+    let names = caves.walk(
+        start,
+        vec![],
+        |ns, _, _, n| ns.push(n),
+        |i| caves.neighbors(i).to_vec(),
+    );
+    assert_eq!(names, ["start", "A", "b", "c", "end", "d"]);
+    assert_eq!(caves.topo_sort(), None);
+    assert_eq!(caves.sccs().len(), 1);
+}
+
+#[test]
+fn test_directed() {
+    let g = Graph::parse("a -> b\nb -> c\nc -> a\nc -> d\nd -> e\ne -> d\n");
+    let name = |scc: &Vec<usize>| {
+        let mut ns = scc.iter().map(|&i| g.name(i)).collect::<Vec<_>>();
+        ns.sort();
+        ns
+    };
+    let sccs = g.sccs().iter().map(name).collect::<Vec<_>>();
+    assert_eq!(sccs, [vec!["d", "e"], vec!["a", "b", "c"]]);
+    assert_eq!(g.topo_sort(), None);
+
+    let dag = Graph::parse("shirt -> tie\ntie -> jacket\npants -> shoes\npants -> jacket\n");
+    let order = dag.topo_sort().unwrap();
+    let names = order.iter().map(|&i| dag.name(i)).collect::<Vec<_>>();
+    assert_eq!(names, ["shirt", "tie", "pants", "jacket", "shoes"]);
+    let ties = Graph::parse("a -> c\nb -> c\nz -> y\n");
+    let order = ties.topo_sort().unwrap();
+    let names = order.iter().map(|&i| ties.name(i)).collect::<Vec<_>>();
+    assert_eq!(names, ["a", "b", "c", "z", "y"]);
+    assert_eq!(dag.paths(0, 2, |_| 1).len(), 1);
+}
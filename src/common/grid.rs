@@ -0,0 +1,212 @@
+use std::ops::{Index, IndexMut};
+use std::slice::ChunksMut;
+
+use super::matrix::{Coord, Matrix, MatrixMut};
+
+/// Fixed-size 2D grid stored row-major in a single `Vec`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    data: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            data: vec![fill; width * height],
+            width,
+            height,
+        }
+    }
+
+    pub fn from_vec(width: usize, data: Vec<T>) -> Self {
+        let height = data.len().checked_div(width).unwrap_or(0);
+        assert_eq!(
+            width * height,
+            data.len(),
+            "{} cells do not fill rows of width {}",
+            data.len(),
+            width
+        );
+        Grid {
+            data,
+            width,
+            height,
+        }
+    }
+
+    pub fn from_rows<R: IntoIterator<Item = T>>(rows: impl IntoIterator<Item = R>) -> Self {
+        let mut data = vec![];
+        let mut width = None;
+        for row in rows {
+            let len = data.len();
+            data.extend(row);
+            let row_width = data.len() - len;
+            assert_eq!(*width.get_or_insert(row_width), row_width, "ragged rows");
+        }
+        Self::from_vec(width.unwrap_or(0), data)
+    }
+
+    /// Parses one row per line, mapping each char through `f`.
+    pub fn parse<F: FnMut(char) -> T>(s: &str, mut f: F) -> Self {
+        Self::from_rows(
+            s.lines()
+                .map(|line| line.chars().map(&mut f).collect::<Vec<_>>()),
+        )
+    }
+
+    /// Parses one row per line of whitespace-separated cells, as printed by
+    /// `Matrix::render` with a separator or padding.
+    #[allow(dead_code)]
+    pub fn parse_words<F: FnMut(&str) -> T>(s: &str, mut f: F) -> Self {
+        Self::from_rows(
+            s.lines()
+                .map(|line| line.split_whitespace().map(&mut f).collect::<Vec<_>>()),
+        )
+    }
+
+    #[allow(dead_code)]
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            data: self.data.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    pub fn contains(&self, coord: Coord) -> bool {
+        coord.x < self.width && coord.y < self.height
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.data[y * self.width..(y + 1) * self.width]
+    }
+
+    #[allow(dead_code)]
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.data[y * self.width..(y + 1) * self.width]
+    }
+
+    #[allow(dead_code)]
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    #[allow(dead_code)]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+    /// Row-major iteration straight over the backing storage.
+    #[allow(dead_code)]
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        let width = self.width;
+        self.data
+            .iter()
+            .enumerate()
+            .map(move |(i, v)| (Coord::new(i % width, i / width), v))
+    }
+
+    #[allow(dead_code)]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Coord, &mut T)> {
+        let width = self.width;
+        self.data
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, v)| (Coord::new(i % width, i / width), v))
+    }
+
+    fn offset(&self, coord: Coord) -> Option<usize> {
+        self.contains(coord).then(|| coord.y * self.width + coord.x)
+    }
+}
+
+impl<T> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coord: Coord) -> &T {
+        let i = self
+            .offset(coord)
+            .unwrap_or_else(|| panic!("{} out of bounds", coord));
+        &self.data[i]
+    }
+}
+
+impl<T> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, coord: Coord) -> &mut T {
+        let i = self
+            .offset(coord)
+            .unwrap_or_else(|| panic!("{} out of bounds", coord));
+        &mut self.data[i]
+    }
+}
+
+impl<T> From<Vec<Vec<T>>> for Grid<T> {
+    fn from(rows: Vec<Vec<T>>) -> Self {
+        Self::from_rows(rows)
+    }
+}
+
+impl<T: Copy> Matrix for Grid<T> {
+    type Item = T;
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn get_coord(&self, coord: Coord) -> Option<T> {
+        self.offset(coord).map(|i| self.data[i])
+    }
+
+    fn transpose(&self) -> Self {
+        let data = (0..self.width)
+            .flat_map(|x| (0..self.height).map(move |y| self.data[y * self.width + x]))
+            .collect();
+        Grid {
+            data,
+            width: self.height,
+            height: self.width,
+        }
+    }
+}
+
+impl<T: Copy> MatrixMut for Grid<T> {
+    type RowsMut<'a>
+        = ChunksMut<'a, T>
+    where
+        T: 'a;
+
+    fn get_coord_mut(&mut self, coord: Coord) -> Option<&mut T> {
+        self.offset(coord).map(|i| &mut self.data[i])
+    }
+
+    fn rows_mut(&mut self) -> Self::RowsMut<'_> {
+        self.data.chunks_mut(self.width.max(1))
+    }
+}
+
+#[test]
+fn test_grid() {
+    let grid = Grid::parse("123\n456\n", |c| c.to_digit(10).unwrap());
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid[Coord::new(2, 1)], 6);
+    assert_eq!(grid.get_coord(Coord::new(3, 0)), None);
+    assert_eq!(grid.values(), vec![1, 2, 3, 4, 5, 6]);
+    assert_eq!(
+        grid.transpose(),
+        Grid::from(vec![vec![1, 4], vec![2, 5], vec![3, 6]])
+    );
+    assert_eq!(
+        grid.neighbors(Coord::new(0, 0), false),
+        vec![(Coord::new(1, 0), 2), (Coord::new(0, 1), 4)]
+    );
+    assert_eq!(Grid::<u32>::parse("", |_| 0).size(), 0);
+}
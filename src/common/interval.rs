@@ -0,0 +1,342 @@
+use std::fmt::Display;
+use std::ops::{Range, RangeInclusive};
+
+use super::math::Int;
+use super::point::PointN;
+
+/// Half-open range `lo..hi` of integers. Empty whenever `lo >= hi`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval<T> {
+    pub lo: T,
+    pub hi: T,
+}
+
+impl<T: Int> Interval<T> {
+    pub fn new(lo: T, hi: T) -> Self {
+        Interval { lo, hi }
+    }
+
+    /// `lo..=hi`. Panics if `hi` is the largest `T`, as the half-open end
+    /// past it does not fit.
+    pub fn inclusive(lo: T, hi: T) -> Self {
+        let Some(end) = hi.checked_add(T::ONE) else {
+            panic!("inclusive interval up to {:?} has no half-open end", hi);
+        };
+        Interval::new(lo, end)
+    }
+
+    /// Everything from `a` to `b` inclusive, in either order. Panics like
+    /// `inclusive`.
+    pub fn between(a: T, b: T) -> Self {
+        Interval::inclusive(a.min(b), a.max(b))
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.lo >= self.hi
+    }
+
+    pub fn len(self) -> T {
+        if self.is_empty() {
+            T::ZERO
+        } else {
+            self.hi - self.lo
+        }
+    }
+
+    pub fn contains(self, x: T) -> bool {
+        self.lo <= x && x < self.hi
+    }
+
+    pub fn overlaps(self, other: Self) -> bool {
+        !self.intersect(other).is_empty()
+    }
+
+    /// Possibly empty.
+    pub fn intersect(self, other: Self) -> Self {
+        Interval::new(self.lo.max(other.lo), self.hi.min(other.hi))
+    }
+
+    /// The single interval covering both, if they overlap or touch.
+    pub fn union(self, other: Self) -> Option<Self> {
+        if self.is_empty() {
+            return Some(other);
+        }
+        if other.is_empty() {
+            return Some(self);
+        }
+        (self.lo <= other.hi && other.lo <= self.hi)
+            .then(|| Interval::new(self.lo.min(other.lo), self.hi.max(other.hi)))
+    }
+
+    /// What is left of `self` below and above `other`; each may be empty.
+    pub fn difference(self, other: Self) -> [Self; 2] {
+        if other.is_empty() {
+            return [self, Interval::new(self.hi, self.hi)];
+        }
+        [
+            Interval::new(self.lo, self.hi.min(other.lo)),
+            Interval::new(self.lo.max(other.hi), self.hi),
+        ]
+    }
+
+    pub fn values(self) -> impl Iterator<Item = T> {
+        let mut cur = self.lo;
+        std::iter::from_fn(move || {
+            (cur < self.hi).then(|| {
+                cur = cur + T::ONE;
+                cur - T::ONE
+            })
+        })
+    }
+}
+
+impl<T: Int> From<Range<T>> for Interval<T> {
+    fn from(r: Range<T>) -> Self {
+        Interval::new(r.start, r.end)
+    }
+}
+
+/// Panics if the range ends at the largest `T`, see `Interval::inclusive`.
+impl<T: Int> From<RangeInclusive<T>> for Interval<T> {
+    fn from(r: RangeInclusive<T>) -> Self {
+        Interval::inclusive(*r.start(), *r.end())
+    }
+}
+
+impl<T: Display> Display for Interval<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..{}", self.lo, self.hi)
+    }
+}
+
+/// Union of intervals, kept sorted with no two overlapping or touching.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RangeSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Int> RangeSet<T> {
+    pub fn new() -> Self {
+        RangeSet { intervals: vec![] }
+    }
+
+    pub fn insert(&mut self, i: impl Into<Interval<T>>) {
+        let mut merged = i.into();
+        if merged.is_empty() {
+            return;
+        }
+        // Everything from `start` to `end` overlaps or touches `merged`.
+        let start = self.intervals.partition_point(|j| j.hi < merged.lo);
+        let end = self.intervals.partition_point(|j| j.lo <= merged.hi);
+        for j in self.intervals[start..end].iter() {
+            merged = merged.union(*j).unwrap();
+        }
+        self.intervals.splice(start..end, [merged]);
+    }
+
+    pub fn remove(&mut self, i: impl Into<Interval<T>>) {
+        let cut = i.into();
+        if cut.is_empty() {
+            return;
+        }
+        let start = self.intervals.partition_point(|j| j.hi <= cut.lo);
+        let end = self.intervals.partition_point(|j| j.lo < cut.hi);
+        let left = self.intervals[start..end]
+            .iter()
+            .flat_map(|j| j.difference(cut))
+            .filter(|j| !j.is_empty())
+            .collect::<Vec<_>>();
+        self.intervals.splice(start..end, left);
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        let i = self.intervals.partition_point(|j| j.hi <= x);
+        self.intervals.get(i).is_some_and(|j| j.contains(x))
+    }
+
+    /// Number of values covered.
+    pub fn len(&self) -> T {
+        self.intervals.iter().fold(T::ZERO, |acc, &i| acc + i.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The disjoint intervals, in increasing order.
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut out = self.clone();
+        for &i in other.intervals.iter() {
+            out.insert(i);
+        }
+        out
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut out = self.clone();
+        for &i in other.intervals.iter() {
+            out.remove(i);
+        }
+        out
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.difference(&self.difference(other))
+    }
+}
+
+impl<T: Int, I: Into<Interval<T>>> FromIterator<I> for RangeSet<T> {
+    fn from_iter<It: IntoIterator<Item = I>>(iter: It) -> Self {
+        let mut set = RangeSet::new();
+        for i in iter {
+            set.insert(i);
+        }
+        set
+    }
+}
+
+/// Half-open box in `D` dimensions, one `Interval` per axis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cuboid<const D: usize> {
+    pub axes: [Interval<i64>; D],
+}
+
+impl<const D: usize> Cuboid<D> {
+    pub fn new(axes: [Interval<i64>; D]) -> Self {
+        Cuboid { axes }
+    }
+
+    /// Every point from `lo` to `hi`, corners included.
+    pub fn inclusive(lo: PointN<D>, hi: PointN<D>) -> Self {
+        Cuboid::new(std::array::from_fn(|i| {
+            Interval::inclusive(lo.0[i], hi.0[i])
+        }))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.axes.iter().any(|a| a.is_empty())
+    }
+
+    pub fn volume(&self) -> u64 {
+        self.axes.iter().map(|a| a.len() as u64).product()
+    }
+
+    pub fn contains(&self, p: PointN<D>) -> bool {
+        self.axes.iter().zip(p.0).all(|(a, x)| a.contains(x))
+    }
+
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        let inner = Cuboid::new(std::array::from_fn(|i| {
+            self.axes[i].intersect(other.axes[i])
+        }));
+        (!inner.is_empty()).then_some(inner)
+    }
+
+    /// Disjoint boxes covering `self` minus `other`, at most `2 * D` of them.
+    /// Slices off the parts below and above `other` one axis at a time.
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        let Some(inner) = self.intersect(other) else {
+            return vec![*self];
+        };
+        let mut rest = *self;
+        let mut pieces = vec![];
+        for axis in 0..D {
+            for side in rest.axes[axis].difference(inner.axes[axis]) {
+                if !side.is_empty() {
+                    let mut piece = rest;
+                    piece.axes[axis] = side;
+                    pieces.push(piece);
+                }
+            }
+            rest.axes[axis] = inner.axes[axis];
+        }
+        pieces
+    }
+}
+
+#[test]
+fn test_interval() {
+    let a = Interval::from(2..=5);
+    assert_eq!((a, a.len()), (Interval::new(2, 6), 4));
+    assert!(a.contains(5) && !a.contains(6));
+    assert_eq!(Interval::between(5, 2), a);
+    assert_eq!(a.intersect((4..9).into()), Interval::new(4, 6));
+    assert!(!a.overlaps((6..9).into()));
+    assert_eq!(a.union((6..9).into()), Some(Interval::new(2, 9)));
+    assert_eq!(a.union((7..9).into()), None);
+    assert_eq!(
+        a.difference((3..4).into()),
+        [Interval::new(2, 3), Interval::new(4, 6)]
+    );
+    assert!(a.difference((0..10).into()).iter().all(|i| i.is_empty()));
+    assert_eq!(a.values().collect::<Vec<_>>(), [2, 3, 4, 5]);
+    assert_eq!(Interval::new(3u8, 1).len(), 0);
+    assert_eq!(Interval::inclusive(0u8, 254).len(), 255);
+}
+
+#[test]
+#[should_panic(expected = "inclusive interval up to 255 has no half-open end")]
+fn test_inclusive_max() {
+    let _ = Interval::from(0..=u8::MAX);
+}
+
+#[test]
+fn test_range_set() {
+    let mut set = [1..3, 10..12, 5..6].into_iter().collect::<RangeSet<i32>>();
+    set.insert(3..5);
+    assert_eq!(
+        set.intervals(),
+        [Interval::new(1, 6), Interval::new(10, 12)]
+    );
+    set.insert(0..=20);
+    assert_eq!(set.intervals(), [Interval::new(0, 21)]);
+    set.remove(4..8);
+    set.remove(10..=10);
+    assert_eq!(set.len(), 4 + 2 + 10);
+    assert!(set.contains(3) && !set.contains(4) && set.contains(11) && !set.contains(21));
+
+    let other = [Interval::new(2, 12)]
+        .into_iter()
+        .collect::<RangeSet<i32>>();
+    assert_eq!(
+        set.intersection(&other).intervals(),
+        [
+            Interval::new(2, 4),
+            Interval::new(8, 10),
+            Interval::new(11, 12)
+        ]
+    );
+    assert_eq!(set.union(&other).intervals(), [Interval::new(0, 21)]);
+    assert_eq!(set.difference(&other).len(), 2 + 9);
+}
+
+#[test]
+fn test_reactor() {
+    let steps = [
+        (true, [10, 10, 10], [12, 12, 12]),
+        (true, [11, 11, 11], [13, 13, 13]),
+        (false, [9, 9, 9], [11, 11, 11]),
+        (true, [10, 10, 10], [10, 10, 10]),
+    ];
+    let mut on: Vec<Cuboid<3>> = vec![];
+    for (state, lo, hi) in steps {
+        let cube = Cuboid::inclusive(PointN(lo), PointN(hi));
+        on = on.iter().flat_map(|c| c.subtract(&cube)).collect();
+        if state {
+            on.push(cube);
+        }
+    }
+    assert_eq!(on.iter().map(Cuboid::volume).sum::<u64>(), 39);
+    assert!(on.iter().any(|c| c.contains(PointN([13, 13, 13]))));
+
+    let outer = Cuboid::inclusive(PointN([0, 0]), PointN([3, 3]));
+    let hole = Cuboid::inclusive(PointN([1, 1]), PointN([2, 2]));
+    let ring = outer.subtract(&hole);
+    assert_eq!(ring.len(), 4);
+    assert_eq!(ring.iter().map(Cuboid::volume).sum::<u64>(), 12);
+    assert!(ring.iter().all(|c| c.intersect(&hole).is_none()));
+}
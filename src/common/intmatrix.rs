@@ -0,0 +1,195 @@
+use std::ops::{Index, IndexMut, Mul};
+
+use super::grid::Grid;
+use super::math::{self, Int};
+use super::matrix::{Coord, Matrix};
+
+/// Dense integer matrix for linear algebra, indexed `(row, col)`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IntMatrix<T>(Grid<T>);
+
+impl<T: Int> IntMatrix<T> {
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        IntMatrix(Grid::new(cols, rows, T::ZERO))
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut m = Self::zeros(n, n);
+        for i in 0..n {
+            m[(i, i)] = T::ONE;
+        }
+        m
+    }
+
+    pub fn from_rows<R: IntoIterator<Item = T>>(rows: impl IntoIterator<Item = R>) -> Self {
+        IntMatrix(Grid::from_rows(rows))
+    }
+
+    /// Step matrix of a population over `n` states, where one member in
+    /// state `s` turns into one member in each of `successors(s)`. Applying
+    /// it to a vector of counts per state advances every member one step.
+    pub fn transition<F, I>(n: usize, successors: F) -> Self
+    where
+        F: Fn(usize) -> I,
+        I: IntoIterator<Item = usize>,
+    {
+        let mut m = Self::zeros(n, n);
+        for from in 0..n {
+            for to in successors(from) {
+                m[(to, from)] = m[(to, from)] + T::ONE;
+            }
+        }
+        m
+    }
+
+    pub fn rows(&self) -> usize {
+        self.0.height()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.0.width()
+    }
+
+    /// `self * v` for a column vector `v`.
+    pub fn apply(&self, v: &[T]) -> Vec<T> {
+        assert_eq!(self.cols(), v.len(), "mismatched vector length");
+        (0..self.rows())
+            .map(|r| {
+                self.0
+                    .row(r)
+                    .iter()
+                    .zip(v)
+                    .fold(T::ZERO, |acc, (&a, &b)| acc + a * b)
+            })
+            .collect()
+    }
+
+    /// `self^exp` by repeated squaring.
+    pub fn pow(&self, exp: u64) -> Self {
+        self.pow_with(exp, |a, b| a * b)
+    }
+
+    /// Product with every entry reduced mod `m`. Entries must be
+    /// nonnegative; intermediate products can't overflow.
+    pub fn mul_mod(&self, rhs: &Self, m: T) -> Self {
+        self.mul_with(rhs, |acc, a, b| {
+            let (a, b, m, acc) = (to_u128(a), to_u128(b), to_u128(m), to_u128(acc));
+            let p = math::mul_mod(a, b, m);
+            let sum = if acc >= m - p { acc - (m - p) } else { acc + p };
+            T::try_from(sum).ok().unwrap()
+        })
+    }
+
+    pub fn pow_mod(&self, exp: u64, m: T) -> Self {
+        self.pow_with(exp, |a, b| a.mul_mod(b, m))
+    }
+
+    fn pow_with(&self, mut exp: u64, mul: impl Fn(&Self, &Self) -> Self) -> Self {
+        assert_eq!(self.rows(), self.cols(), "only square matrices have powers");
+        let mut base = self.clone();
+        let one = Self::identity(self.rows());
+        // Through `mul` so that `pow_mod(0, 1)` is reduced too.
+        let mut acc = mul(&one, &one);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc = mul(&acc, &base);
+            }
+            exp >>= 1;
+            // Skipping the last squaring keeps it from overflowing.
+            if exp > 0 {
+                base = mul(&base, &base);
+            }
+        }
+        acc
+    }
+
+    fn mul_with(&self, rhs: &Self, add_product: impl Fn(T, T, T) -> T) -> Self {
+        assert_eq!(self.cols(), rhs.rows(), "mismatched matrix shapes");
+        let mut out = Self::zeros(self.rows(), rhs.cols());
+        for r in 0..self.rows() {
+            for k in 0..self.cols() {
+                let a = self[(r, k)];
+                for c in 0..rhs.cols() {
+                    out[(r, c)] = add_product(out[(r, c)], a, rhs[(k, c)]);
+                }
+            }
+        }
+        out
+    }
+}
+
+fn to_u128<T: Int>(x: T) -> u128 {
+    x.try_into()
+        .unwrap_or_else(|_| panic!("negative entry {:?} in modular product", x))
+}
+
+impl<T: Int> Mul for &IntMatrix<T> {
+    type Output = IntMatrix<T>;
+
+    fn mul(self, rhs: &IntMatrix<T>) -> IntMatrix<T> {
+        self.mul_with(rhs, |acc, a, b| acc + a * b)
+    }
+}
+
+impl<T> Index<(usize, usize)> for IntMatrix<T> {
+    type Output = T;
+
+    fn index(&self, (r, c): (usize, usize)) -> &T {
+        &self.0[Coord::new(c, r)]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for IntMatrix<T> {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut T {
+        &mut self.0[Coord::new(c, r)]
+    }
+}
+
+impl<T: Copy> Matrix for IntMatrix<T> {
+    type Item = T;
+
+    fn width(&self) -> usize {
+        self.0.width()
+    }
+
+    fn height(&self) -> usize {
+        self.0.height()
+    }
+
+    fn get_coord(&self, coord: Coord) -> Option<T> {
+        self.0.get_coord(coord)
+    }
+
+    fn transpose(&self) -> Self {
+        IntMatrix(self.0.transpose())
+    }
+}
+
+#[test]
+fn test_int_matrix() {
+    let fib = IntMatrix::from_rows([[1u64, 1], [1, 0]]);
+    assert_eq!(fib.pow(0), IntMatrix::identity(2));
+    assert_eq!(fib.pow(10)[(0, 1)], 55);
+    assert_eq!(fib.pow(90)[(0, 1)], 2880067194370816120);
+    assert_eq!(fib.pow_mod(90, 1000)[(0, 1)], 120);
+    assert_eq!(IntMatrix::from_rows([[2u64]]).pow_mod(0, 1)[(0, 0)], 0);
+    let big = IntMatrix::from_rows([[u128::MAX - 1, 1], [1, 0]]);
+    assert_eq!(big.mul_mod(&big, u128::MAX)[(0, 0)], 2);
+    let near = IntMatrix::from_rows([[u128::MAX - 1, u128::MAX - 1]]);
+    let col = IntMatrix::from_rows([[u128::MAX - 1], [u128::MAX - 1]]);
+    assert_eq!(near.mul_mod(&col, u128::MAX)[(0, 0)], 2);
+
+    let a = IntMatrix::from_rows([[1i32, 2, 3], [4, 5, 6]]);
+    let b = a.transpose();
+    assert_eq!(&a * &b, IntMatrix::from_rows([[14, 32], [32, 77]]));
+    assert_eq!(a.apply(&[1, 0, -1]), [-2, -2]);
+    assert_eq!((b.rows(), b.cols()), (3, 2));
+}
+
+#[test]
+fn test_transition() {
+    // Each rabbit pair becomes an adult pair (1), and adults also breed (0).
+    let rabbits = IntMatrix::<u64>::transition(2, |s| if s == 0 { vec![1] } else { vec![0, 1] });
+    assert_eq!(rabbits, IntMatrix::from_rows([[0, 1], [1, 1]]));
+    assert_eq!(rabbits.pow(10).apply(&[1, 0]), [34, 55]);
+}
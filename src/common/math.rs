@@ -0,0 +1,222 @@
+use std::fmt::Debug;
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+/// Primitive integers, signed or not.
+pub trait Int:
+    Copy
+    + Ord
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + TryFrom<u128>
+    + TryInto<u128>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! int {
+    ($($t: ty),*) => {
+        $(impl Int for $t {
+            const ZERO: $t = 0;
+            const ONE: $t = 1;
+
+            fn checked_add(self, rhs: $t) -> Option<$t> {
+                <$t>::checked_add(self, rhs)
+            }
+        })*
+    };
+}
+
+int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+fn abs<T: Int>(x: T) -> T {
+    if x < T::ZERO {
+        T::ZERO - x
+    } else {
+        x
+    }
+}
+
+/// Always nonnegative; `gcd(0, 0)` is 0.
+pub fn gcd<T: Int>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a, b);
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    abs(a)
+}
+
+/// Always nonnegative; 0 if either side is.
+pub fn lcm<T: Int>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        return T::ZERO;
+    }
+    abs(a) / gcd(a, b) * abs(b)
+}
+
+/// Least common multiple of all of `xs`, e.g. when independent cycles
+/// line up again. 1 for no numbers.
+pub fn lcm_all<T: Int>(xs: impl IntoIterator<Item = T>) -> T {
+    xs.into_iter().fold(T::ONE, lcm)
+}
+
+/// `(g, x, y)` with `a * x + b * y == g == gcd(a, b)`.
+pub fn ext_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (1, 0);
+    let (mut y0, mut y1) = (0, 1);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+    if r0 < 0 {
+        (-r0, -x0, -y0)
+    } else {
+        (r0, x0, y0)
+    }
+}
+
+/// `a * b % m` without overflowing, even when `a * b` exceeds `u128`.
+pub fn mul_mod(a: u128, b: u128, m: u128) -> u128 {
+    let (a, mut b) = (a % m, b % m);
+    if let Some(p) = a.checked_mul(b) {
+        return p % m;
+    }
+    // Double and add, keeping every intermediate below `m`.
+    let add = |x: u128, y: u128| if x >= m - y { x - (m - y) } else { x + y };
+    let (mut acc, mut a) = (0, a);
+    while b > 0 {
+        if b & 1 == 1 {
+            acc = add(acc, a);
+        }
+        a = add(a, a);
+        b >>= 1;
+    }
+    acc
+}
+
+/// `base^exp % m`.
+pub fn pow_mod(base: u128, exp: u128, m: u128) -> u128 {
+    let (mut base, mut exp, mut acc) = (base % m, exp, 1 % m);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = mul_mod(acc, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    acc
+}
+
+/// `x` in `0..m` with `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime.
+pub fn mod_inv(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = ext_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// Solves `x ≡ r (mod m)` for every `(r, m)` at once, returning `(x, lcm)`
+/// with `x` in `0..lcm`. The moduli need not be coprime; `None` if the
+/// congruences contradict each other.
+pub fn crt(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    congruences.iter().try_fold((0, 1), |(r1, m1), &(r2, m2)| {
+        let r2 = r2.rem_euclid(m2);
+        let g = gcd(m1, m2);
+        if (r2 - r1) % g != 0 {
+            return None;
+        }
+        let (m1g, m2g) = (m1 / g, m2 / g);
+        // r1 + m1 * k ≡ r2 (mod m2), with k taken mod m2 / g.
+        let diff = ((r2 - r1) / g).rem_euclid(m2g) as u128;
+        let k = mul_mod(diff, mod_inv(m1g, m2g)? as u128, m2g as u128) as i128;
+        let m = m1g.checked_mul(m2)?;
+        Some(((r1 + m1 * k).rem_euclid(m), m))
+    })
+}
+
+/// Largest `r` with `r * r <= n`. Panics on negative `n`.
+pub fn isqrt<T: Int>(n: T) -> T {
+    let Ok(n) = n.try_into() else {
+        panic!("isqrt of negative {:?}", n);
+    };
+    let root = isqrt_u128(n);
+    T::try_from(root).ok().unwrap()
+}
+
+fn isqrt_u128(n: u128) -> u128 {
+    // One result bit at a time, from the top, never squaring past `n`.
+    let mut root = 0u128;
+    for bit in (0..64).rev() {
+        let next = root | 1 << bit;
+        if next.checked_mul(next).is_some_and(|sq| sq <= n) {
+            root = next;
+        }
+    }
+    root
+}
+
+#[test]
+fn test_gcd_lcm() {
+    assert_eq!(gcd(12, 18), 6);
+    assert_eq!(gcd(-12i64, 18), 6);
+    assert_eq!(gcd(0u8, 7), 7);
+    assert_eq!(gcd(0, 0), 0);
+    assert_eq!(lcm(4usize, 6), 12);
+    assert_eq!(lcm(-4i32, 6), 12);
+    assert_eq!(lcm(0, 5), 0);
+    assert_eq!(lcm_all([2u64, 3, 4, 5]), 60);
+    assert_eq!(lcm_all(Vec::<u32>::new()), 1);
+}
+
+#[test]
+fn test_ext_gcd() {
+    for (a, b) in [(240, 46), (-7, 3), (0, 5), (17, 0), (6, -9)] {
+        let (g, x, y) = ext_gcd(a, b);
+        assert_eq!(g, gcd(a, b));
+        assert_eq!(a * x + b * y, g);
+    }
+}
+
+#[test]
+fn test_modular() {
+    assert_eq!(pow_mod(2, 10, 1000), 24);
+    assert_eq!(pow_mod(7, 0, 1), 0);
+    let p = (1u128 << 127) - 1;
+    assert_eq!(pow_mod(3, p - 1, p), 1);
+    assert_eq!(mul_mod(u128::MAX, u128::MAX, p), mul_mod(1, 1, p));
+    assert_eq!(mul_mod(p - 1, p - 1, p), 1);
+    assert_eq!(mod_inv(3, 11), Some(4));
+    assert_eq!(mod_inv(-3, 11), Some(7));
+    assert_eq!(mod_inv(4, 10), None);
+}
+
+#[test]
+fn test_crt() {
+    assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+    assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+    assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+    assert_eq!(crt(&[(-1, 5)]), Some((4, 5)));
+    assert_eq!(crt(&[]), Some((0, 1)));
+    let big = (1i128 << 61) - 1;
+    assert_eq!(
+        crt(&[(3, big), (5, 1 << 40)]).map(|(x, m)| (x % big, x % (1 << 40), m)),
+        Some((3, 5, big << 40))
+    );
+}
+
+#[test]
+fn test_isqrt() {
+    assert_eq!(isqrt(0), 0);
+    assert_eq!(isqrt(15u8), 3);
+    assert_eq!(isqrt(16i64), 4);
+    assert_eq!(isqrt(u64::MAX), u32::MAX as u64);
+    assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+    assert_eq!(isqrt(1u128 << 100), 1 << 50);
+}
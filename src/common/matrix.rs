@@ -1,9 +1,22 @@
+use std::collections::HashMap;
 use std::fmt::Display;
+use std::iter::{Enumerate, Map, Skip, Take};
+use std::slice::IterMut;
+
+use super::dir::{Dir4, Dir8};
+use super::point::Point;
+use super::view::{Transform, View};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Coord {
-    x: usize,
-    y: usize,
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Coord {
+    pub fn new(x: usize, y: usize) -> Self {
+        Coord { x, y }
+    }
 }
 
 impl Display for Coord {
@@ -12,28 +25,282 @@ impl Display for Coord {
     }
 }
 
+/// What lies past the edges of a matrix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Boundary<T> {
+    /// Nothing: neighbours stop at the edges.
+    Clip,
+    /// The opposite edge, as on a torus.
+    Wrap,
+    /// An endless border of the given value. It has no coordinates, so
+    /// only value queries such as `get_point` and `neighbor_values` see it.
+    Pad(T),
+}
+
 pub trait Matrix
 where
     Self: Sized,
 {
-    type Item;
+    type Item: Copy;
     fn width(&self) -> usize;
     fn height(&self) -> usize;
-    fn size(&self) -> usize;
     fn get_coord(&self, coord: Coord) -> Option<Self::Item>;
-    fn get_coord_mut(&mut self, coord: Coord) -> Option<&mut Self::Item>;
     fn transpose(&self) -> Self;
-    fn neighbors(&self, coord: Coord, diagonals: bool) -> Vec<(Coord, Self::Item)>;
-    fn neighbors_mut(&mut self, coord: Coord, diagonals: bool) -> Vec<(Coord, &mut Self::Item)>;
-    fn flat_iter(&self) -> MatrixIterator<Self>;
-    fn flat_iter_mut(&mut self) -> MatrixIteratorMut<Self>;
+
+    fn boundary(&self) -> Boundary<Self::Item> {
+        Boundary::Clip
+    }
+
+    fn with_boundary(self, boundary: Boundary<Self::Item>) -> Bounded<Self> {
+        Bounded {
+            matrix: self,
+            boundary,
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.width() * self.height()
+    }
+
+    /// Maps a signed position onto the matrix according to `boundary`.
+    fn resolve(&self, p: Point) -> Option<Coord> {
+        let (w, h) = (self.width() as i64, self.height() as i64);
+        match self.boundary() {
+            Boundary::Wrap if w > 0 && h > 0 => Some(Coord {
+                x: p.x.rem_euclid(w) as usize,
+                y: p.y.rem_euclid(h) as usize,
+            }),
+            _ => Coord::try_from(p)
+                .ok()
+                .filter(|c| c.x < self.width() && c.y < self.height()),
+        }
+    }
+
+    fn step(&self, coord: Coord, delta: Point) -> Option<Coord> {
+        self.resolve(Point::from(coord) + delta)
+    }
+
+    fn get_point(&self, p: Point) -> Option<Self::Item> {
+        match (self.resolve(p), self.boundary()) {
+            (Some(c), _) => self.get_coord(c),
+            (None, Boundary::Pad(v)) => Some(v),
+            (None, _) => None,
+        }
+    }
+
+    /// Distinct cells one step of `deltas` away from `coord`, in order of
+    /// first appearance. Deltas move at most one cell along each axis.
+    fn neighbor_coords(
+        &self,
+        coord: Coord,
+        deltas: impl IntoIterator<Item = Point>,
+    ) -> impl Iterator<Item = Coord> {
+        let mut repeats = Repeats::new(self, coord);
+        deltas
+            .into_iter()
+            .filter_map(move |d| self.step(coord, d).filter(|&c| repeats.fresh(c)))
+    }
+
+    fn neighbors(&self, coord: Coord, diagonals: bool) -> Vec<(Coord, Self::Item)> {
+        self.neighbor_coords(coord, Point::ORIGIN.neighbors(diagonals))
+            .filter_map(|c| self.get_coord(c).map(|v| (c, v)))
+            .collect()
+    }
+
+    /// Like `neighbors`, but also sees `Boundary::Pad` cells.
+    fn neighbor_values(&self, coord: Coord, diagonals: bool) -> impl Iterator<Item = Self::Item> {
+        let mut repeats = Repeats::new(self, coord);
+        Point::from(coord)
+            .neighbors(diagonals)
+            .filter_map(move |p| match self.resolve(p) {
+                Some(c) => repeats.fresh(c).then(|| self.get_coord(c)).flatten(),
+                None => self.get_point(p),
+            })
+    }
+
+    fn neighbors4(&self, coord: Coord) -> impl Iterator<Item = (Coord, Self::Item)> {
+        self.neighbor_coords(coord, Dir4::ALL.map(Dir4::delta))
+            .filter_map(move |c| self.get_coord(c).map(|v| (c, v)))
+    }
+
+    fn neighbors8(&self, coord: Coord) -> impl Iterator<Item = (Coord, Self::Item)> {
+        self.neighbor_coords(coord, Dir8::ALL.map(Dir8::delta))
+            .filter_map(move |c| self.get_coord(c).map(|v| (c, v)))
+    }
+
+    fn flat_iter(&self) -> MatrixIterator<'_, Self> {
+        MatrixIterator {
+            matrix: self,
+            pos: Coord { x: 0, y: 0 },
+        }
+    }
 
     fn values(&self) -> Vec<Self::Item> {
         self.flat_iter().map(|(_, v)| v).collect()
     }
 
-    fn values_mut(&mut self) -> Vec<&mut Self::Item> {
-        self.flat_iter_mut().map(|(_, v)| v).collect()
+    /// Cells from `start` (inclusive) stepping in `dir` until the edge.
+    fn line(&self, start: Coord, dir: impl Into<Dir8>) -> Line<'_, Self> {
+        Line {
+            matrix: self,
+            pos: Point::from(start),
+            step: dir.into().delta(),
+        }
+    }
+
+    /// Like `line`, but starting from the cell after `from`.
+    fn ray(&self, from: Coord, dir: impl Into<Dir8>) -> Line<'_, Self> {
+        let dir = dir.into();
+        let mut ray = self.line(from, dir);
+        ray.pos += dir.delta();
+        ray
+    }
+
+    fn rows(&self) -> impl Iterator<Item = Line<'_, Self>> {
+        (0..self.height()).map(|y| self.line(Coord { x: 0, y }, Dir8::E))
+    }
+
+    fn cols(&self) -> impl Iterator<Item = Line<'_, Self>> {
+        (0..self.width()).map(|x| self.line(Coord { x, y: 0 }, Dir8::S))
+    }
+
+    /// Lines running down and to the right, from the bottom left corner to
+    /// the top right one.
+    fn diagonals(&self) -> impl Iterator<Item = Line<'_, Self>> {
+        let left = (0..diagonal_rows(self)).rev().map(|y| Coord { x: 0, y });
+        let top = (1..self.width()).map(|x| Coord { x, y: 0 });
+        left.chain(top).map(|c| self.line(c, Dir8::SE))
+    }
+
+    /// Lines running down and to the left, from the top left corner to the
+    /// bottom right one.
+    fn anti_diagonals(&self) -> impl Iterator<Item = Line<'_, Self>> {
+        let top = (0..self.width()).map(|x| Coord { x, y: 0 });
+        let right = (1..diagonal_rows(self)).map(|y| Coord {
+            x: self.width() - 1,
+            y,
+        });
+        top.chain(right).map(|c| self.line(c, Dir8::SW))
+    }
+
+    /// Every `k` × `k` sub-view, row-major by top left corner. Panics if
+    /// `k` is 0, like `slice::windows`.
+    fn windows(&self, k: usize) -> impl Iterator<Item = View<'_, Self>> {
+        assert!(k > 0, "window size must be nonzero");
+        let (w, h) = (
+            (self.width() + 1).saturating_sub(k),
+            (self.height() + 1).saturating_sub(k),
+        );
+        (0..h).flat_map(move |y| (0..w).map(move |x| self.subview(Coord { x, y }, k, k)))
+    }
+
+    fn view(&self) -> View<'_, Self> {
+        View::new(self, Coord { x: 0, y: 0 }, self.width(), self.height())
+    }
+
+    /// Borrowed `width` × `height` rectangle with its top left at `origin`.
+    fn subview(&self, origin: Coord, width: usize, height: usize) -> View<'_, Self> {
+        View::new(self, origin, width, height)
+    }
+
+    fn transformed(&self, t: Transform) -> View<'_, Self> {
+        self.view().transform(t)
+    }
+
+    /// Clockwise.
+    fn rotate90(&self) -> View<'_, Self> {
+        self.transformed(Transform::ROTATE90)
+    }
+
+    fn rotate180(&self) -> View<'_, Self> {
+        self.transformed(Transform::ROTATE180)
+    }
+
+    fn rotate270(&self) -> View<'_, Self> {
+        self.transformed(Transform::ROTATE270)
+    }
+
+    /// Mirrors left to right.
+    fn flip_h(&self) -> View<'_, Self> {
+        self.transformed(Transform::FLIP_H)
+    }
+
+    /// Mirrors top to bottom.
+    fn flip_v(&self) -> View<'_, Self> {
+        self.transformed(Transform::FLIP_V)
+    }
+
+    fn symmetries(&self) -> impl Iterator<Item = View<'_, Self>> {
+        Transform::ALL.into_iter().map(|t| self.transformed(t))
+    }
+
+    /// Displays the matrix one row per line, each cell formatted by `cell`.
+    fn render<F, D>(&self, cell: F) -> Render<'_, Self, F>
+    where
+        F: Fn(Self::Item) -> D,
+        D: Display,
+    {
+        Render {
+            matrix: self,
+            cell,
+            highlighted: HashMap::new(),
+            pad: 0,
+            sep: "",
+        }
+    }
+}
+
+/// Mutable access, handed out row by row so that iterators can borrow
+/// disjoint cells without going through `get_coord_mut`.
+pub trait MatrixMut: Matrix {
+    type RowsMut<'a>: Iterator<Item = &'a mut [Self::Item]>
+    where
+        Self: 'a;
+
+    fn get_coord_mut(&mut self, coord: Coord) -> Option<&mut Self::Item>;
+    fn rows_mut(&mut self) -> Self::RowsMut<'_>;
+
+    /// Cells in the half-open rectangle `start..end`, clipped to the matrix.
+    #[allow(dead_code)]
+    fn region_mut(&mut self, start: Coord, end: Coord) -> MatrixIteratorMut<'_, Self> {
+        MatrixIteratorMut::new_with_bounds(self, start, end)
+    }
+
+    #[allow(dead_code)]
+    fn neighbors_mut(
+        &mut self,
+        coord: Coord,
+        diagonals: bool,
+    ) -> impl Iterator<Item = (Coord, &mut Self::Item)> {
+        let mut targets = [None; 8];
+        let neighbors = self.neighbor_coords(coord, Point::ORIGIN.neighbors(diagonals));
+        for (t, c) in targets.iter_mut().zip(neighbors) {
+            *t = Some(c);
+        }
+        // Wrapped neighbours may sit at the far edges, so skip straight to
+        // each one instead of scanning the rectangle between them.
+        targets.sort_unstable_by_key(|t| t.map(|c| (c.y, c.x)));
+        let mut rows = self.rows_mut();
+        let (mut next_y, mut rest, mut next_x) = (0, Default::default(), 0);
+        targets.into_iter().flatten().map(move |c| {
+            if c.y >= next_y {
+                rest = rows.nth(c.y - next_y).unwrap();
+                (next_y, next_x) = (c.y + 1, 0);
+            }
+            let (cell, tail) = std::mem::take(&mut rest)[c.x - next_x..]
+                .split_first_mut()
+                .unwrap();
+            (rest, next_x) = (tail, c.x + 1);
+            (c, cell)
+        })
+    }
+
+    fn flat_iter_mut(&mut self) -> MatrixIteratorMut<'_, Self> {
+        MatrixIteratorMut::new(self)
+    }
+
+    fn values_mut(&mut self) -> impl Iterator<Item = &mut Self::Item> {
+        self.flat_iter_mut().map(|(_, v)| v)
     }
 }
 
@@ -41,75 +308,134 @@ impl<T: Copy> Matrix for Vec<Vec<T>> {
     type Item = T;
 
     fn width(&self) -> usize {
-        self[0].len()
+        self.first().map_or(0, Vec::len)
     }
 
     fn height(&self) -> usize {
         self.len()
     }
 
-    fn size(&self) -> usize {
-        self.width() * self.height()
-    }
-
     fn get_coord(&self, coord: Coord) -> Option<T> {
         self.get(coord.y).and_then(|row| row.get(coord.x).copied())
     }
 
-    fn get_coord_mut(&mut self, coord: Coord) -> Option<&mut Self::Item> {
+    fn transpose(&self) -> Vec<Vec<T>> {
+        (0..self.width())
+            .map(|j| self.iter().map(|row| row[j]).collect())
+            .collect()
+    }
+}
+
+impl<T: Copy> MatrixMut for Vec<Vec<T>> {
+    type RowsMut<'a>
+        = Map<IterMut<'a, Vec<T>>, fn(&mut Vec<T>) -> &mut [T]>
+    where
+        T: 'a;
+
+    fn get_coord_mut(&mut self, coord: Coord) -> Option<&mut T> {
         self.get_mut(coord.y).and_then(|row| row.get_mut(coord.x))
     }
 
-    fn neighbors(&self, coord: Coord, diagonals: bool) -> Vec<(Coord, T)> {
-        let Coord { x, y } = coord;
-        (y.saturating_sub(1)..=y + 1)
-            .flat_map(|ny| {
-                (x.saturating_sub(1)..=x + 1)
-                    .filter_map(|nx| {
-                        let c = Coord { x: nx, y: ny };
-                        ((nx != x || ny != y) && (diagonals || nx == x || ny == y))
-                            .then_some(c)
-                            .and_then(|c| self.get_coord(c))
-                            .map(|v| (c, v))
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .collect()
+    fn rows_mut(&mut self) -> Self::RowsMut<'_> {
+        self.iter_mut().map(Vec::as_mut_slice)
     }
+}
 
-    fn neighbors_mut(&mut self, coord: Coord, diagonals: bool) -> Vec<(Coord, &mut T)> {
-        let Coord { x, y } = coord;
-        return MatrixIteratorMut::new_with_bounds(
-            self,
-            Coord {
-                x: x.saturating_sub(1),
-                y: y.saturating_sub(1),
-            },
-            Coord {
-                x: (x + 2).min(self.width()),
-                y: (y + 2).min(self.height()),
-            },
-        )
-        .filter(|(c, _)| (c.x != x || c.y != y) && (diagonals || c.x == x || c.y == y))
-        .collect();
+/// Rows diagonal lines can start from: none without columns, where every
+/// line would be empty.
+fn diagonal_rows<M: Matrix>(matrix: &M) -> usize {
+    if matrix.width() == 0 {
+        0
+    } else {
+        matrix.height()
     }
+}
 
-    fn transpose(&self) -> Vec<Vec<T>> {
-        assert!(!self.is_empty());
-        (0..self[0].len())
-            .map(|j| self.iter().map(|row| row[j]).collect())
-            .collect()
+/// Cells a neighbour query has already yielded. Only a wrapped matrix 1 or
+/// 2 cells across reaches the same cell, or `coord` itself, by several
+/// deltas; everywhere else every cell is fresh and nothing is recorded.
+struct Repeats {
+    coord: Coord,
+    seen: Option<[Option<Coord>; 8]>,
+}
+
+impl Repeats {
+    fn new<M: Matrix>(matrix: &M, coord: Coord) -> Self {
+        let small_torus = matches!(matrix.boundary(), Boundary::Wrap)
+            && (matrix.width() < 3 || matrix.height() < 3);
+        Repeats {
+            coord,
+            seen: small_torus.then_some([None; 8]),
+        }
     }
 
-    fn flat_iter(&self) -> MatrixIterator<Self> {
-        MatrixIterator {
-            matrix: self,
-            pos: Coord { x: 0, y: 0 },
+    fn fresh(&mut self, c: Coord) -> bool {
+        let Some(seen) = self.seen.as_mut() else {
+            return true;
+        };
+        if c == self.coord || seen.contains(&Some(c)) {
+            return false;
+        }
+        if let Some(slot) = seen.iter_mut().find(|s| s.is_none()) {
+            *slot = Some(c);
         }
+        true
     }
+}
 
-    fn flat_iter_mut(&mut self) -> MatrixIteratorMut<Self> {
-        MatrixIteratorMut::new(self)
+/// A matrix with a boundary policy other than the default `Clip`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bounded<M: Matrix> {
+    matrix: M,
+    boundary: Boundary<M::Item>,
+}
+
+impl<M: Matrix> Bounded<M> {
+    pub fn inner(&self) -> &M {
+        &self.matrix
+    }
+
+    pub fn into_inner(self) -> M {
+        self.matrix
+    }
+}
+
+impl<M: Matrix> Matrix for Bounded<M> {
+    type Item = M::Item;
+
+    fn width(&self) -> usize {
+        self.matrix.width()
+    }
+
+    fn height(&self) -> usize {
+        self.matrix.height()
+    }
+
+    fn get_coord(&self, coord: Coord) -> Option<M::Item> {
+        self.matrix.get_coord(coord)
+    }
+
+    fn transpose(&self) -> Self {
+        self.matrix.transpose().with_boundary(self.boundary)
+    }
+
+    fn boundary(&self) -> Boundary<M::Item> {
+        self.boundary
+    }
+}
+
+impl<M: MatrixMut> MatrixMut for Bounded<M> {
+    type RowsMut<'a>
+        = M::RowsMut<'a>
+    where
+        M: 'a;
+
+    fn get_coord_mut(&mut self, coord: Coord) -> Option<&mut M::Item> {
+        self.matrix.get_coord_mut(coord)
+    }
+
+    fn rows_mut(&mut self) -> Self::RowsMut<'_> {
+        self.matrix.rows_mut()
     }
 }
 
@@ -142,35 +468,139 @@ where
     }
 }
 
-pub struct MatrixIteratorMut<'a, M>
+/// Straight run of cells, see `Matrix::line`.
+pub struct Line<'a, M> {
+    matrix: &'a M,
+    pos: Point,
+    step: Point,
+}
+
+impl<'a, M: Matrix> Line<'a, M> {
+    pub fn values(self) -> impl Iterator<Item = M::Item> + 'a {
+        self.map(|(_, v)| v)
+    }
+}
+
+impl<M: Matrix> Iterator for Line<'_, M> {
+    type Item = (Coord, M::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let c = Coord::try_from(self.pos).ok()?;
+        let v = self.matrix.get_coord(c)?;
+        self.pos += self.step;
+        Some((c, v))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ansi {
+    Bold = 1,
+    Red = 31,
+    Green = 32,
+    Yellow = 33,
+    Blue = 34,
+    Magenta = 35,
+    Cyan = 36,
+}
+
+/// Removes the colour codes `Render` puts around highlighted cells.
+#[allow(dead_code)]
+pub fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|&c| c == 'm');
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// See `Matrix::render`.
+pub struct Render<'a, M, F> {
+    matrix: &'a M,
+    cell: F,
+    highlighted: HashMap<Coord, Ansi>,
+    pad: usize,
+    sep: &'a str,
+}
+
+impl<'a, M, F> Render<'a, M, F> {
+    /// Colours `coords`, overriding earlier highlights of the same cells.
+    pub fn highlight(mut self, coords: impl IntoIterator<Item = Coord>, color: Ansi) -> Self {
+        self.highlighted
+            .extend(coords.into_iter().map(|c| (c, color)));
+        self
+    }
+
+    /// Right-aligns every cell to `width` chars.
+    pub fn pad(mut self, width: usize) -> Self {
+        self.pad = width;
+        self
+    }
+
+    pub fn sep(mut self, sep: &'a str) -> Self {
+        self.sep = sep;
+        self
+    }
+}
+
+impl<M, F, D> Display for Render<'_, M, F>
 where
     M: Matrix,
+    F: Fn(M::Item) -> D,
+    D: Display,
 {
-    matrix: &'a mut M,
-    pos: Coord,
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.matrix.rows() {
+            for (c, v) in row {
+                if c.x > 0 {
+                    f.write_str(self.sep)?;
+                }
+                let cell = format!("{:>1$}", (self.cell)(v), self.pad);
+                match self.highlighted.get(&c) {
+                    Some(&color) => write!(f, "\x1b[{}m{}\x1b[0m", color as u8, cell)?,
+                    None => f.write_str(&cell)?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct MatrixIteratorMut<'a, M>
+where
+    M: MatrixMut + 'a,
+{
+    rows: Take<Skip<Enumerate<M::RowsMut<'a>>>>,
+    row: Option<(usize, Enumerate<IterMut<'a, M::Item>>)>,
     start: Coord,
     end: Coord,
 }
 
 impl<'a, M> MatrixIteratorMut<'a, M>
 where
-    M: Matrix,
+    M: MatrixMut,
 {
     fn new(matrix: &'a mut M) -> Self {
-        Self::new_with_bounds(
-            matrix,
-            Coord { x: 0, y: 0 },
-            Coord {
-                x: matrix.width(),
-                y: matrix.height(),
-            },
-        )
+        let end = Coord {
+            x: matrix.width(),
+            y: matrix.height(),
+        };
+        Self::new_with_bounds(matrix, Coord { x: 0, y: 0 }, end)
     }
 
     fn new_with_bounds(matrix: &'a mut M, start: Coord, end: Coord) -> Self {
         Self {
-            matrix,
-            pos: start,
+            rows: matrix
+                .rows_mut()
+                .enumerate()
+                .skip(start.y)
+                .take(end.y.saturating_sub(start.y)),
+            row: None,
             start,
             end,
         }
@@ -179,23 +609,25 @@ where
 
 impl<'a, M> Iterator for MatrixIteratorMut<'a, M>
 where
-    M: Matrix,
+    M: MatrixMut,
 {
     type Item = (Coord, &'a mut M::Item);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.pos.y >= self.end.y {
-            None
-        } else {
-            unsafe {
-                let v: *mut M::Item = self.matrix.get_coord_mut(self.pos).unwrap();
-                self.pos.x += 1;
-                if self.pos.x >= self.end.x {
-                    self.pos.x = self.start.x;
-                    self.pos.y += 1;
+        loop {
+            if let Some((y, cells)) = &mut self.row {
+                if let Some((i, v)) = cells.next() {
+                    let coord = Coord {
+                        x: self.start.x + i,
+                        y: *y,
+                    };
+                    return Some((coord, v));
                 }
-                Some((self.pos, v.as_mut().unwrap()))
             }
+            let (y, row) = self.rows.next()?;
+            let end = self.end.x.min(row.len());
+            let start = self.start.x.min(end);
+            self.row = Some((y, row[start..end].iter_mut().enumerate()));
         }
     }
 }
@@ -226,3 +658,220 @@ fn test_neighbors() {
         ]
     );
 }
+
+#[test]
+fn test_neighbors4_8() {
+    let xs = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+    let values =
+        |it: &mut dyn Iterator<Item = (Coord, i32)>| it.map(|(_, v)| v).collect::<Vec<_>>();
+    assert_eq!(
+        values(&mut xs.neighbors4(Coord { x: 1, y: 1 })),
+        [1, 5, 7, 3]
+    );
+    assert_eq!(values(&mut xs.neighbors4(Coord { x: 0, y: 0 })), [1, 3]);
+    assert_eq!(
+        values(&mut xs.neighbors8(Coord { x: 1, y: 1 })),
+        [1, 2, 5, 8, 7, 6, 3, 0]
+    );
+    assert_eq!(values(&mut xs.neighbors8(Coord { x: 2, y: 0 })), [5, 4, 1]);
+}
+
+#[test]
+fn test_neighbors_mut() {
+    let mut xs = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+    let corner = xs
+        .neighbors_mut(Coord { x: 0, y: 0 }, true)
+        .map(|(c, v)| (c, *v))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        corner,
+        vec![
+            (Coord { x: 1, y: 0 }, 1),
+            (Coord { x: 0, y: 1 }, 3),
+            (Coord { x: 1, y: 1 }, 4),
+        ]
+    );
+    for (c, v) in xs.neighbors_mut(Coord { x: 2, y: 2 }, false) {
+        assert_eq!(c.y * 3 + c.x, *v);
+        *v += 10;
+    }
+    assert_eq!(xs, vec![vec![0, 1, 2], vec![3, 4, 15], vec![6, 17, 8]]);
+}
+
+#[test]
+fn test_flat_iter_mut() {
+    let mut xs = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+    for (c, v) in xs.flat_iter_mut() {
+        assert_eq!(c.y * 3 + c.x, *v);
+    }
+    let region = xs
+        .region_mut(Coord { x: 1, y: 1 }, Coord { x: 5, y: 5 })
+        .map(|(c, v)| (c, *v))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        region,
+        vec![
+            (Coord { x: 1, y: 1 }, 4),
+            (Coord { x: 2, y: 1 }, 5),
+            (Coord { x: 1, y: 2 }, 7),
+            (Coord { x: 2, y: 2 }, 8),
+        ]
+    );
+    assert_eq!(
+        xs.region_mut(Coord { x: 2, y: 0 }, Coord { x: 1, y: 3 })
+            .count(),
+        0
+    );
+    let mut ragged = vec![vec![0, 1], vec![2]];
+    assert_eq!(
+        ragged.values_mut().map(|v| *v).collect::<Vec<_>>(),
+        [0, 1, 2]
+    );
+}
+
+#[test]
+fn test_boundary() {
+    let xs = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+    let torus = xs.clone().with_boundary(Boundary::Wrap);
+    let values = |ns: Vec<(Coord, i32)>| ns.into_iter().map(|(_, v)| v).collect::<Vec<_>>();
+    assert_eq!(
+        values(torus.neighbors(Coord { x: 0, y: 0 }, true)),
+        [8, 6, 7, 2, 1, 5, 3, 4]
+    );
+    assert_eq!(values(xs.neighbors(Coord { x: 0, y: 0 }, true)), [1, 3, 4]);
+    assert_eq!(torus.get_point(Point::new(-1, 4)), Some(5));
+
+    let padded = xs.clone().with_boundary(Boundary::Pad(-1));
+    assert_eq!(padded.neighbors(Coord { x: 0, y: 0 }, false).len(), 2);
+    assert_eq!(
+        padded
+            .neighbor_values(Coord { x: 0, y: 0 }, false)
+            .collect::<Vec<_>>(),
+        [-1, -1, 1, 3]
+    );
+
+    let mut torus = torus;
+    for (c, v) in torus.neighbors_mut(Coord { x: 2, y: 2 }, false) {
+        assert!(c != Coord { x: 2, y: 2 });
+        *v += 10;
+    }
+    assert_eq!(
+        torus.into_inner(),
+        vec![vec![0, 1, 12], vec![3, 4, 15], vec![16, 17, 8]]
+    );
+
+    let mut wide = vec![vec![0; 4]; 3].with_boundary(Boundary::Wrap);
+    let corner = wide
+        .neighbors_mut(Coord { x: 0, y: 0 }, true)
+        .map(|(c, _)| (c.x, c.y))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        corner,
+        [
+            (1, 0),
+            (3, 0),
+            (0, 1),
+            (1, 1),
+            (3, 1),
+            (0, 2),
+            (1, 2),
+            (3, 2)
+        ]
+    );
+}
+
+#[test]
+fn test_small_torus() {
+    let origin = Coord { x: 0, y: 0 };
+    let mut pair = vec![vec![1, 2]].with_boundary(Boundary::Wrap);
+    let right = vec![(Coord { x: 1, y: 0 }, 2)];
+    assert_eq!(pair.neighbors(origin, false), right);
+    assert_eq!(pair.neighbors(origin, true), right);
+    assert_eq!(pair.neighbors4(origin).collect::<Vec<_>>(), right);
+    assert_eq!(pair.neighbors8(origin).collect::<Vec<_>>(), right);
+    assert_eq!(pair.neighbor_values(origin, true).collect::<Vec<_>>(), [2]);
+    let mutable = pair
+        .neighbors_mut(origin, true)
+        .map(|(c, v)| (c, *v))
+        .collect::<Vec<_>>();
+    assert_eq!(mutable, right);
+
+    let row = vec![vec![1, 2, 3]].with_boundary(Boundary::Wrap);
+    assert_eq!(
+        row.neighbors(origin, false),
+        [(Coord { x: 2, y: 0 }, 3), (Coord { x: 1, y: 0 }, 2)]
+    );
+}
+
+#[test]
+fn test_lines() {
+    let xs = vec![vec![0, 1, 2], vec![3, 4, 5]];
+    let lines = |it: &mut dyn Iterator<Item = Line<'_, Vec<Vec<i32>>>>| {
+        it.map(|l| l.values().collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(lines(&mut xs.rows()), [vec![0, 1, 2], vec![3, 4, 5]]);
+    assert_eq!(lines(&mut xs.cols()), [vec![0, 3], vec![1, 4], vec![2, 5]]);
+    assert_eq!(
+        lines(&mut xs.diagonals()),
+        [vec![3], vec![0, 4], vec![1, 5], vec![2]]
+    );
+    assert_eq!(
+        lines(&mut xs.anti_diagonals()),
+        [vec![0], vec![1, 3], vec![2, 4], vec![5]]
+    );
+    assert_eq!(
+        xs.ray(Coord { x: 2, y: 1 }, Dir4::Left).collect::<Vec<_>>(),
+        [(Coord { x: 1, y: 1 }, 4), (Coord { x: 0, y: 1 }, 3)]
+    );
+    assert_eq!(xs.ray(Coord { x: 2, y: 1 }, Dir8::SE).count(), 0);
+
+    let sums = xs.windows(2).map(|w| w.values().iter().sum::<i32>());
+    assert_eq!(sums.collect::<Vec<_>>(), [8, 12]);
+    assert_eq!(xs.windows(3).count(), 0);
+    assert_eq!(Vec::<Vec<i32>>::new().diagonals().count(), 0);
+    let hollow: Vec<Vec<i32>> = vec![vec![]; 2];
+    assert_eq!(hollow.diagonals().count(), 0);
+    assert_eq!(hollow.anti_diagonals().count(), 0);
+    assert_eq!(
+        super::grid::Grid::new(0, 3, 0u8).anti_diagonals().count(),
+        0
+    );
+}
+
+#[test]
+#[should_panic(expected = "window size must be nonzero")]
+fn test_windows_zero() {
+    let _ = vec![vec![0; 2]; 2].windows(0);
+}
+
+#[test]
+fn test_render() {
+    use super::grid::Grid;
+
+    let xs = vec![vec![1, 12, 3], vec![4, 5, 60]];
+    let plain = xs.render(|v| v).pad(2).sep(" ").to_string();
+    assert_eq!(plain, " 1 12  3\n 4  5 60\n");
+    assert_eq!(
+        Grid::parse_words(&plain, |w| w.parse().unwrap()),
+        Grid::from(xs.clone())
+    );
+
+    let coloured = xs
+        .render(|v| v)
+        .highlight([Coord { x: 1, y: 1 }, Coord { x: 2, y: 0 }], Ansi::Green)
+        .highlight([Coord { x: 2, y: 0 }], Ansi::Bold)
+        .to_string();
+    assert_eq!(coloured, "112\x1b[1m3\x1b[0m\n4\x1b[32m5\x1b[0m60\n");
+
+    let marked = xs.render(|v| if v > 9 { '#' } else { '.' });
+    let text = strip_ansi(
+        &marked
+            .highlight([Coord { x: 0, y: 0 }], Ansi::Bold)
+            .to_string(),
+    );
+    assert_eq!(
+        Grid::parse(&text, |c| c == '#'),
+        Grid::from(vec![vec![false, true, false], vec![false, false, true]])
+    );
+}
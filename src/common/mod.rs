@@ -1,7 +1,31 @@
+pub mod automaton;
+#[allow(dead_code)]
+pub mod bimap;
+pub mod bitgrid;
 pub mod counter;
+pub mod csp;
+pub mod dir;
+#[allow(dead_code)]
+pub mod dsu;
+#[allow(dead_code)]
+pub mod graph;
+pub mod grid;
+#[allow(dead_code)]
+pub mod interval;
+pub mod intmatrix;
+#[allow(dead_code)]
+pub mod math;
 pub mod matrix;
+#[allow(dead_code)]
+pub mod point;
+#[allow(dead_code)]
 pub mod reverse;
 pub mod run;
+#[allow(dead_code)]
+pub mod sparse;
+#[allow(dead_code)]
+pub mod view;
 pub mod walk;
 
+#[allow(dead_code)]
 mod template;
@@ -0,0 +1,271 @@
+use std::fmt::Display;
+use std::hash::Hash;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use super::matrix::Coord;
+
+/// Signed 2D point, for grids that grow past their origin.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub fn new(x: i64, y: i64) -> Self {
+        Point { x, y }
+    }
+
+    pub fn manhattan(self, other: Point) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    /// The 4 or 8 surrounding points, in row-major order.
+    pub fn neighbors(self, diagonals: bool) -> impl Iterator<Item = Point> {
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| Point::new(dx, dy)))
+            .filter(move |d| *d != Point::ORIGIN && (diagonals || d.x == 0 || d.y == 0))
+            .map(move |d| self + d)
+    }
+
+    pub fn min(self, other: Point) -> Point {
+        Point::new(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    pub fn max(self, other: Point) -> Point {
+        Point::new(self.x.max(other.x), self.y.max(other.y))
+    }
+}
+
+/// Points a sparse grid can be keyed by.
+pub trait GridPoint: Copy + Eq + Hash {
+    fn component_min(self, other: Self) -> Self;
+    fn component_max(self, other: Self) -> Self;
+}
+
+impl GridPoint for Point {
+    fn component_min(self, other: Point) -> Point {
+        self.min(other)
+    }
+
+    fn component_max(self, other: Point) -> Point {
+        self.max(other)
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<{}, {}>", self.x, self.y)
+    }
+}
+
+impl From<Coord> for Point {
+    fn from(c: Coord) -> Self {
+        Point::new(c.x as i64, c.y as i64)
+    }
+}
+
+impl TryFrom<Point> for Coord {
+    type Error = Point;
+
+    fn try_from(p: Point) -> Result<Self, Self::Error> {
+        match (usize::try_from(p.x), usize::try_from(p.y)) {
+            (Ok(x), Ok(y)) => Ok(Coord::new(x, y)),
+            _ => Err(p),
+        }
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Point) -> Point {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Point) -> Point {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<i64> for Point {
+    type Output = Point;
+
+    fn mul(self, rhs: i64) -> Point {
+        Point::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Point) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Point) {
+        *self = *self - rhs;
+    }
+}
+
+/// Signed point in `D` dimensions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PointN<const D: usize>(pub [i64; D]);
+
+impl<const D: usize> PointN<D> {
+    pub const ORIGIN: PointN<D> = PointN([0; D]);
+
+    pub fn manhattan(self, other: Self) -> i64 {
+        self.0.iter().zip(other.0).map(|(a, b)| (a - b).abs()).sum()
+    }
+
+    /// The `2 * D` orthogonal neighbours, or with `diagonals` the full Moore
+    /// neighbourhood of `3^D - 1` points. The first axis varies fastest.
+    pub fn neighbors(self, diagonals: bool) -> impl Iterator<Item = Self> {
+        (0..3usize.pow(D as u32)).filter_map(move |mut n| {
+            let mut d = [0; D];
+            for x in d.iter_mut() {
+                *x = (n % 3) as i64 - 1;
+                n /= 3;
+            }
+            let moved = d.iter().filter(|&&x| x != 0).count();
+            (moved == 1 || diagonals && moved > 0).then(|| self + PointN(d))
+        })
+    }
+
+    fn zip_with(self, other: Self, f: impl Fn(i64, i64) -> i64) -> Self {
+        PointN(std::array::from_fn(|i| f(self.0[i], other.0[i])))
+    }
+}
+
+impl<const D: usize> Default for PointN<D> {
+    fn default() -> Self {
+        Self::ORIGIN
+    }
+}
+
+impl<const D: usize> GridPoint for PointN<D> {
+    fn component_min(self, other: Self) -> Self {
+        self.zip_with(other, i64::min)
+    }
+
+    fn component_max(self, other: Self) -> Self {
+        self.zip_with(other, i64::max)
+    }
+}
+
+impl<const D: usize> Display for PointN<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<")?;
+        for (i, x) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", x)?;
+        }
+        write!(f, ">")
+    }
+}
+
+impl From<Point> for PointN<2> {
+    fn from(p: Point) -> Self {
+        PointN([p.x, p.y])
+    }
+}
+
+impl<const D: usize> Add for PointN<D> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        self.zip_with(rhs, |a, b| a + b)
+    }
+}
+
+impl<const D: usize> Sub for PointN<D> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.zip_with(rhs, |a, b| a - b)
+    }
+}
+
+impl<const D: usize> Neg for PointN<D> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        PointN(self.0.map(|x| -x))
+    }
+}
+
+impl<const D: usize> Mul<i64> for PointN<D> {
+    type Output = Self;
+
+    fn mul(self, rhs: i64) -> Self {
+        PointN(self.0.map(|x| x * rhs))
+    }
+}
+
+impl<const D: usize> AddAssign for PointN<D> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const D: usize> SubAssign for PointN<D> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+#[test]
+fn test_point() {
+    let p = Point::new(0, -2);
+    assert_eq!(p + Point::new(3, 1) * 2, Point::new(6, 0));
+    assert_eq!(-p - Point::new(1, 1), Point::new(-1, 1));
+    assert_eq!(p.manhattan(Point::new(-3, 2)), 7);
+    assert_eq!(
+        p.neighbors(false).collect::<Vec<_>>(),
+        [(0, -3), (-1, -2), (1, -2), (0, -1)].map(|(x, y)| Point::new(x, y))
+    );
+    assert_eq!(p.neighbors(true).count(), 8);
+    assert_eq!(Coord::try_from(p), Err(p));
+    assert_eq!(Coord::try_from(Point::new(1, 2)), Ok(Coord::new(1, 2)));
+}
+
+#[test]
+fn test_point_n() {
+    let p = PointN([1, -1, 0]);
+    assert_eq!(p + PointN([1, 1, 1]) * 2, PointN([3, 1, 2]));
+    assert_eq!(p.manhattan(-p), 4);
+    assert_eq!(p.neighbors(false).count(), 6);
+    assert_eq!(p.neighbors(true).count(), 26);
+    assert_eq!(PointN::<4>::ORIGIN.neighbors(true).count(), 80);
+    assert!(p.neighbors(false).all(|n| n.manhattan(p) == 1));
+    assert_eq!(
+        PointN::from(Point::ORIGIN)
+            .neighbors(true)
+            .collect::<Vec<_>>(),
+        Point::ORIGIN
+            .neighbors(true)
+            .map(PointN::from)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        PointN([0, 5]).component_min(PointN([2, -5])),
+        PointN([0, -5])
+    );
+}
@@ -1,16 +1,36 @@
 use std::collections::HashMap;
+use std::hash::Hash;
 
 pub trait Reverse {
-    fn reverse(&self) -> Self;
+    type Output;
+    type Error;
+    fn reverse(&self) -> Result<Self::Output, Self::Error>;
 }
 
+/// Fails with the first value found under two keys, instead of silently
+/// keeping only one of them.
 impl<K, V> Reverse for HashMap<K, V>
 where
-    K: Copy,
-    V: Copy,
-    HashMap<K, V>: FromIterator<(V, K)>,
+    K: Clone,
+    V: Clone + Eq + Hash,
 {
-    fn reverse(&self) -> Self {
-        self.iter().map(|(&k, &v)| (v, k)).collect()
+    type Output = HashMap<V, K>;
+    type Error = V;
+
+    fn reverse(&self) -> Result<HashMap<V, K>, V> {
+        let mut reversed = HashMap::with_capacity(self.len());
+        for (k, v) in self {
+            if reversed.insert(v.clone(), k.clone()).is_some() {
+                return Err(v.clone());
+            }
+        }
+        Ok(reversed)
     }
 }
+
+#[test]
+fn test_reverse() {
+    let m = HashMap::from([(1, 'a'), (2, 'b')]);
+    assert_eq!(m.reverse(), Ok(HashMap::from([('a', 1), ('b', 2)])));
+    assert_eq!(HashMap::from([(1, 'a'), (2, 'a')]).reverse(), Err('a'));
+}
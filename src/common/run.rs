@@ -1,12 +1,12 @@
 use std::{fmt::Debug, time::Instant};
 
 pub trait Run {
-    type Input;
+    type Input<'a>;
     type Output: Debug + PartialEq;
 
-    fn parse(&self, s: &str) -> Self::Input;
-    fn part1(&self, input: &Self::Input) -> Self::Output;
-    fn part2(&self, input: &Self::Input) -> Self::Output;
+    fn parse<'a>(&self, s: &'a str) -> Self::Input<'a>;
+    fn part1(&self, input: &Self::Input<'_>) -> Self::Output;
+    fn part2(&self, input: &Self::Input<'_>) -> Self::Output;
 
     fn solve(
         &self,
@@ -53,9 +53,23 @@ pub trait Run {
 
 #[macro_export]
 macro_rules! vec_input {
+    (&str) => {
+        type Input<'a> = Vec<&'a str>;
+        fn parse<'a>(&self, s: &'a str) -> Self::Input<'a> {
+            s.lines().collect()
+        }
+    };
+
+    (&str, $sep: literal) => {
+        type Input<'a> = Vec<&'a str>;
+        fn parse<'a>(&self, s: &'a str) -> Self::Input<'a> {
+            s.trim().split($sep).collect()
+        }
+    };
+
     ($Input: ty) => {
-        type Input = Vec<$Input>;
-        fn parse(&self, s: &str) -> Self::Input {
+        type Input<'a> = Vec<$Input>;
+        fn parse<'a>(&self, s: &'a str) -> Self::Input<'a> {
             use std::str::FromStr;
             s.lines()
                 .map(|line| <$Input>::from_str(line).unwrap())
@@ -64,8 +78,8 @@ macro_rules! vec_input {
     };
 
     ($Input: ty, $sep: literal) => {
-        type Input = Vec<$Input>;
-        fn parse(&self, s: &str) -> Self::Input {
+        type Input<'a> = Vec<$Input>;
+        fn parse<'a>(&self, s: &'a str) -> Self::Input<'a> {
             use std::str::FromStr;
             s.trim()
                 .split($sep)
@@ -78,8 +92,8 @@ macro_rules! vec_input {
 #[macro_export]
 macro_rules! matrix_input {
     ($Input: ty) => {
-        type Input = Vec<Vec<$Input>>;
-        fn parse(&self, s: &str) -> Self::Input {
+        type Input<'a> = Vec<Vec<$Input>>;
+        fn parse<'a>(&self, s: &'a str) -> Self::Input<'a> {
             s.lines()
                 .map(|line| {
                     line.chars()
@@ -91,6 +105,16 @@ macro_rules! matrix_input {
     };
 }
 
+#[macro_export]
+macro_rules! grid_input {
+    ($Input: ty) => {
+        type Input<'a> = $crate::common::grid::Grid<$Input>;
+        fn parse<'a>(&self, s: &'a str) -> Self::Input<'a> {
+            $crate::common::grid::Grid::parse(s, |c| c.to_digit(10).unwrap() as $Input)
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! solve {
     ($year: ident, $day: ident) => {{
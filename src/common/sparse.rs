@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use super::point::{GridPoint, Point};
+
+/// Unbounded grid over signed points, in 2 dimensions unless keyed by a
+/// `PointN`. Unset cells read as `default`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T, P: GridPoint = Point> {
+    cells: HashMap<P, T>,
+    default: T,
+    bounds: Option<(P, P)>,
+}
+
+impl<T> SparseGrid<T> {
+    /// Parses one row per line with the first char at the origin. Cells
+    /// for which `f` returns `None` are left unset.
+    pub fn parse<F: FnMut(char) -> Option<T>>(s: &str, default: T, mut f: F) -> Self {
+        let mut grid = Self::new(default);
+        for (y, line) in s.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                if let Some(v) = f(c) {
+                    grid.insert(Point::new(x as i64, y as i64), v);
+                }
+            }
+        }
+        grid
+    }
+
+    /// Every point in the bounding box grown by `margin`, row-major.
+    pub fn points(&self, margin: i64) -> impl Iterator<Item = Point> {
+        let (min, max) = self
+            .bounds
+            .map(|(min, max)| {
+                (
+                    min - Point::new(margin, margin),
+                    max + Point::new(margin, margin),
+                )
+            })
+            .unwrap_or((Point::ORIGIN, Point::new(-1, -1)));
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point::new(x, y)))
+    }
+}
+
+impl<T, P: GridPoint> SparseGrid<T, P> {
+    pub fn new(default: T) -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+            default,
+            bounds: None,
+        }
+    }
+
+    pub fn get(&self, p: P) -> &T {
+        self.cells.get(&p).unwrap_or(&self.default)
+    }
+
+    /// Sets `p`, growing the bounding box to include it.
+    pub fn insert(&mut self, p: P, v: T) -> Option<T> {
+        self.grow(p);
+        self.cells.insert(p, v)
+    }
+
+    /// Unsetting a cell does not shrink the bounding box.
+    pub fn remove(&mut self, p: P) -> Option<T> {
+        self.cells.remove(&p)
+    }
+
+    pub fn get_mut(&mut self, p: P) -> &mut T
+    where
+        T: Clone,
+    {
+        self.grow(p);
+        self.cells.entry(p).or_insert_with(|| self.default.clone())
+    }
+
+    pub fn is_set(&self, p: P) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    pub fn default_value(&self) -> &T {
+        &self.default
+    }
+
+    /// Changes what unset cells read as, e.g. for infinite images whose
+    /// background flips every step.
+    pub fn set_default(&mut self, default: T) {
+        self.default = default;
+    }
+
+    /// Inclusive `(min, max)` corners of every point ever set.
+    pub fn bounds(&self) -> Option<(P, P)> {
+        self.bounds
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (P, &T)> {
+        self.cells.iter().map(|(&p, v)| (p, v))
+    }
+
+    fn grow(&mut self, p: P) {
+        self.bounds = Some(match self.bounds {
+            Some((min, max)) => (min.component_min(p), max.component_max(p)),
+            None => (p, p),
+        });
+    }
+}
+
+#[test]
+fn test_sparse_grid() {
+    let mut grid = SparseGrid::parse("#.\n.#\n", false, |c| (c == '#').then_some(true));
+    assert_eq!(grid.len(), 2);
+    assert_eq!(grid.bounds(), Some((Point::new(0, 0), Point::new(1, 1))));
+    *grid.get_mut(Point::new(-2, 3)) = true;
+    assert!(*grid.get(Point::new(-2, 3)));
+    assert!(!*grid.get(Point::new(-1, 3)));
+    assert_eq!(grid.bounds(), Some((Point::new(-2, 0), Point::new(1, 3))));
+    assert_eq!(grid.points(1).count(), 6 * 6);
+    grid.set_default(true);
+    assert!(*grid.get(Point::new(100, 100)));
+}
+
+#[test]
+fn test_sparse_grid_n() {
+    use super::point::PointN;
+
+    let mut cubes = SparseGrid::new(false);
+    cubes.insert(PointN([0, 0, 0, 0]), true);
+    cubes.insert(PointN([1, -2, 0, 3]), true);
+    assert_eq!(
+        cubes.bounds(),
+        Some((PointN([0, -2, 0, 0]), PointN([1, 0, 0, 3])))
+    );
+    let active = |p: PointN<4>| p.neighbors(true).filter(|&n| *cubes.get(n)).count();
+    assert_eq!(active(PointN([1, -1, 0, 1])), 1);
+    assert_eq!(active(PointN([1, -1, 0, 2])), 1);
+    assert_eq!(active(PointN([0, -1, 0, 1])), 1);
+    assert_eq!(active(PointN([9, 9, 9, 9])), 0);
+}
@@ -6,18 +6,18 @@ use crate::vec_input;
 pub struct Runner {}
 
 impl Run for Runner {
-    type Input = Vec<usize>;
+    type Input<'a> = Vec<usize>;
     type Output = usize;
 
-    fn parse(&self, s: &str) -> Self::Input {
+    fn parse<'a>(&self, s: &'a str) -> Self::Input<'a> {
         todo!()
     }
 
-    fn part1(&self, input: &Self::Input) -> Self::Output {
+    fn part1(&self, input: &Self::Input<'_>) -> Self::Output {
         todo!()
     }
 
-    fn part2(&self, input: &Self::Input) -> Self::Output {
+    fn part2(&self, input: &Self::Input<'_>) -> Self::Output {
         todo!()
     }
 }
@@ -0,0 +1,185 @@
+use super::matrix::{Coord, Matrix};
+
+/// One of the 8 symmetries of a rectangle. A view coordinate is mirrored
+/// along the flagged axes first, then swapped if `swap` is set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Transform {
+    pub swap: bool,
+    pub flip_x: bool,
+    pub flip_y: bool,
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform::new(false, false, false);
+    pub const ROTATE90: Transform = Transform::new(true, true, false);
+    pub const ROTATE180: Transform = Transform::new(false, true, true);
+    pub const ROTATE270: Transform = Transform::new(true, false, true);
+    pub const FLIP_H: Transform = Transform::new(false, true, false);
+    pub const FLIP_V: Transform = Transform::new(false, false, true);
+    pub const TRANSPOSE: Transform = Transform::new(true, false, false);
+    pub const ANTI_TRANSPOSE: Transform = Transform::new(true, true, true);
+
+    pub const ALL: [Transform; 8] = [
+        Self::IDENTITY,
+        Self::ROTATE90,
+        Self::ROTATE180,
+        Self::ROTATE270,
+        Self::FLIP_H,
+        Self::FLIP_V,
+        Self::TRANSPOSE,
+        Self::ANTI_TRANSPOSE,
+    ];
+
+    const fn new(swap: bool, flip_x: bool, flip_y: bool) -> Self {
+        Transform {
+            swap,
+            flip_x,
+            flip_y,
+        }
+    }
+
+    /// The transform that applies `self` first and `next` on top of it.
+    pub fn then(self, next: Transform) -> Transform {
+        let (fx, fy) = if next.swap {
+            (self.flip_y, self.flip_x)
+        } else {
+            (self.flip_x, self.flip_y)
+        };
+        Transform {
+            swap: self.swap ^ next.swap,
+            flip_x: fx ^ next.flip_x,
+            flip_y: fy ^ next.flip_y,
+        }
+    }
+}
+
+/// Borrowed, possibly rotated or mirrored, rectangle of another matrix.
+#[derive(Debug)]
+pub struct View<'a, M> {
+    matrix: &'a M,
+    origin: Coord,
+    width: usize,
+    height: usize,
+    transform: Transform,
+}
+
+impl<M> Clone for View<'_, M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M> Copy for View<'_, M> {}
+
+impl<'a, M: Matrix> View<'a, M> {
+    /// `width` and `height` are clipped to what `matrix` has past `origin`.
+    pub fn new(matrix: &'a M, origin: Coord, width: usize, height: usize) -> Self {
+        View {
+            matrix,
+            origin,
+            width: width.min(matrix.width().saturating_sub(origin.x)),
+            height: height.min(matrix.height().saturating_sub(origin.y)),
+            transform: Transform::IDENTITY,
+        }
+    }
+
+    pub fn transform(self, t: Transform) -> Self {
+        View {
+            transform: self.transform.then(t),
+            ..self
+        }
+    }
+
+    fn source(&self, coord: Coord) -> Option<Coord> {
+        if coord.x >= self.width() || coord.y >= self.height() {
+            return None;
+        }
+        let Transform {
+            swap,
+            flip_x,
+            flip_y,
+        } = self.transform;
+        let x = if flip_x {
+            self.width() - 1 - coord.x
+        } else {
+            coord.x
+        };
+        let y = if flip_y {
+            self.height() - 1 - coord.y
+        } else {
+            coord.y
+        };
+        let (x, y) = if swap { (y, x) } else { (x, y) };
+        Some(Coord::new(self.origin.x + x, self.origin.y + y))
+    }
+}
+
+impl<M: Matrix> Matrix for View<'_, M> {
+    type Item = M::Item;
+
+    fn width(&self) -> usize {
+        if self.transform.swap {
+            self.height
+        } else {
+            self.width
+        }
+    }
+
+    fn height(&self) -> usize {
+        if self.transform.swap {
+            self.width
+        } else {
+            self.height
+        }
+    }
+
+    fn get_coord(&self, coord: Coord) -> Option<M::Item> {
+        self.source(coord).and_then(|c| self.matrix.get_coord(c))
+    }
+
+    fn transpose(&self) -> Self {
+        self.transform(Transform::TRANSPOSE)
+    }
+}
+
+#[cfg(test)]
+fn rows<M: Matrix<Item = i32>>(m: M) -> Vec<Vec<i32>> {
+    (0..m.height())
+        .map(|y| {
+            (0..m.width())
+                .map(|x| m.get_coord(Coord::new(x, y)).unwrap())
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_transforms() {
+    let xs = vec![vec![1, 2, 3], vec![4, 5, 6]];
+    assert_eq!(rows(xs.rotate90()), [[4, 1], [5, 2], [6, 3]]);
+    assert_eq!(rows(xs.rotate180()), [[6, 5, 4], [3, 2, 1]]);
+    assert_eq!(rows(xs.rotate270()), [[3, 6], [2, 5], [1, 4]]);
+    assert_eq!(rows(xs.flip_h()), [[3, 2, 1], [6, 5, 4]]);
+    assert_eq!(rows(xs.flip_v()), [[4, 5, 6], [1, 2, 3]]);
+    assert_eq!(rows(xs.view().transpose()), xs.transpose());
+    assert_eq!(rows(xs.rotate90().transpose()), rows(xs.flip_v()));
+    assert_eq!(rows(xs.rotate90().rotate90()), rows(xs.rotate180()));
+    for t in Transform::ALL {
+        for u in Transform::ALL {
+            assert_eq!(
+                rows(xs.transformed(t).transform(u)),
+                rows(xs.transformed(t).transformed(u))
+            );
+        }
+    }
+
+    let mut all = xs.symmetries().map(rows).collect::<Vec<_>>();
+    all.sort();
+    all.dedup();
+    assert_eq!(all.len(), 8);
+
+    let sub = xs.subview(Coord::new(1, 0), 5, 2);
+    assert_eq!(rows(sub), [[2, 3], [5, 6]]);
+    assert_eq!(rows(sub.rotate90()), [[5, 2], [6, 3]]);
+    assert_eq!(sub.get_coord(Coord::new(2, 0)), None);
+}
@@ -1,14 +1,27 @@
-use std::collections::{HashSet, VecDeque};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::{Add, ControlFlow};
 
+use super::grid::Grid;
 use super::matrix::{Coord, Matrix};
+use super::point::GridPoint;
+use super::sparse::SparseGrid;
 
 pub trait Walk {
     type Item;
-    type Index;
+    type Index: Copy + Eq + Hash;
+
+    fn value(&self, index: Self::Index) -> Self::Item;
+
+    /// Expected number of visited cells, to size the visited set.
+    fn size_hint(&self) -> usize {
+        0
+    }
 
     fn walk<F, G, I, St>(
         &self,
-        init_coord: Coord,
+        init_coord: Self::Index,
         initial_st: St,
         walk_fn: F,
         neighbors_fn: G,
@@ -16,7 +29,62 @@ pub trait Walk {
     where
         F: FnMut(&mut St, Option<Self::Index>, Self::Index, Self::Item),
         G: Fn(Self::Index) -> I,
-        I: IntoIterator<Item = Self::Index>;
+        I: IntoIterator<Item = Self::Index>,
+    {
+        bfs(
+            init_coord,
+            initial_st,
+            |i| self.value(i),
+            walk_fn,
+            neighbors_fn,
+            self.size_hint(),
+        )
+    }
+
+    /// `astar` where stepping onto a cell costs `cost_fn(from, to, value)`.
+    #[allow(dead_code)]
+    fn astar<C, N, I, K, G, H>(
+        &self,
+        start: Self::Index,
+        neighbors_fn: N,
+        cost_fn: K,
+        is_goal: G,
+        heuristic: H,
+    ) -> ShortestPaths<Self::Index, C>
+    where
+        C: Copy + Ord + Add<Output = C> + Default,
+        N: FnMut(&Self::Index) -> I,
+        I: IntoIterator<Item = Self::Index>,
+        K: Fn(Self::Index, Self::Index, Self::Item) -> C,
+        G: FnMut(&Self::Index) -> bool,
+        H: FnMut(&Self::Index) -> C,
+    {
+        astar(
+            start,
+            neighbors_fn,
+            |&from, &to| cost_fn(from, to, self.value(to)),
+            is_goal,
+            heuristic,
+        )
+    }
+
+    #[allow(dead_code)]
+    fn dijkstra<C, N, I, K, G>(
+        &self,
+        start: Self::Index,
+        neighbors_fn: N,
+        cost_fn: K,
+        is_goal: G,
+    ) -> ShortestPaths<Self::Index, C>
+    where
+        C: Copy + Ord + Add<Output = C> + Default,
+        N: FnMut(&Self::Index) -> I,
+        I: IntoIterator<Item = Self::Index>,
+        K: Fn(Self::Index, Self::Index, Self::Item) -> C,
+        G: FnMut(&Self::Index) -> bool,
+    {
+        self.astar(start, neighbors_fn, cost_fn, is_goal, |_| C::default())
+    }
 }
 
 impl<M> Walk for M
@@ -26,28 +94,607 @@ where
     type Item = M::Item;
     type Index = Coord;
 
-    fn walk<F, G, I, St>(
+    fn value(&self, coord: Coord) -> M::Item {
+        self.get_coord(coord).unwrap()
+    }
+
+    fn size_hint(&self) -> usize {
+        self.size()
+    }
+}
+
+impl<T: Copy, P: GridPoint> Walk for SparseGrid<T, P> {
+    type Item = T;
+    type Index = P;
+
+    fn value(&self, p: P) -> T {
+        *self.get(p)
+    }
+
+    fn size_hint(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Result of `dijkstra` or `astar`.
+#[derive(Clone, Debug)]
+#[allow(dead_code)]
+pub struct ShortestPaths<S, C> {
+    #[allow(dead_code)]
+    pub start: S,
+    /// The goal state reached, if any.
+    pub goal: Option<S>,
+    /// Best known cost of every state reached. Only final for states that
+    /// were expanded, which is all of them when no goal was found.
+    pub dist: HashMap<S, C>,
+    /// The state each reached state was best reached from.
+    pub prev: HashMap<S, S>,
+}
+
+impl<S: Clone + Eq + Hash, C: Copy> ShortestPaths<S, C> {
+    #[allow(dead_code)]
+    pub fn distance(&self) -> Option<C> {
+        self.goal.as_ref().map(|g| self.dist[g])
+    }
+
+    #[allow(dead_code)]
+    pub fn path(&self) -> Option<Vec<S>> {
+        self.path_to(self.goal.as_ref()?)
+    }
+
+    /// States from `start` to `target`, both included.
+    #[allow(dead_code)]
+    pub fn path_to(&self, target: &S) -> Option<Vec<S>> {
+        if !self.dist.contains_key(target) {
+            return None;
+        }
+        let mut path = vec![target.clone()];
+        while let Some(prev) = self.prev.get(path.last().unwrap()) {
+            path.push(prev.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+#[allow(dead_code)]
+pub fn dijkstra<S, C, N, I, K, G>(
+    start: S,
+    neighbors_fn: N,
+    cost_fn: K,
+    is_goal: G,
+) -> ShortestPaths<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    K: FnMut(&S, &S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    astar(start, neighbors_fn, cost_fn, is_goal, |_| C::default())
+}
+
+/// Cheapest path from `start` to the first state passing `is_goal`. The
+/// `heuristic` must never overestimate the remaining cost. Without a goal,
+/// every reachable state is settled.
+#[allow(dead_code)]
+pub fn astar<S, C, N, I, K, G, H>(
+    start: S,
+    mut neighbors_fn: N,
+    mut cost_fn: K,
+    mut is_goal: G,
+    mut heuristic: H,
+) -> ShortestPaths<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    K: FnMut(&S, &S) -> C,
+    G: FnMut(&S) -> bool,
+    H: FnMut(&S) -> C,
+{
+    let mut paths = ShortestPaths {
+        start: start.clone(),
+        goal: None,
+        dist: HashMap::from([(start.clone(), C::default())]),
+        prev: HashMap::new(),
+    };
+    let mut queue = BinaryHeap::from([Queued {
+        estimate: heuristic(&start),
+        cost: C::default(),
+        state: start,
+    }]);
+    while let Some(Queued { cost, state, .. }) = queue.pop() {
+        if paths.dist[&state] < cost {
+            continue;
+        }
+        if is_goal(&state) {
+            paths.goal = Some(state);
+            break;
+        }
+        for next in neighbors_fn(&state) {
+            let next_cost = cost + cost_fn(&state, &next);
+            if paths.dist.get(&next).is_none_or(|&c| next_cost < c) {
+                paths.dist.insert(next.clone(), next_cost);
+                paths.prev.insert(next.clone(), state.clone());
+                queue.push(Queued {
+                    estimate: next_cost + heuristic(&next),
+                    cost: next_cost,
+                    state: next,
+                });
+            }
+        }
+    }
+    paths
+}
+
+/// Min-heap entry ordered by estimated total cost only.
+#[allow(dead_code)]
+struct Queued<S, C> {
+    estimate: C,
+    cost: C,
+    state: S,
+}
+
+impl<S, C: Ord> Ord for Queued<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.cmp(&self.estimate)
+    }
+}
+
+impl<S, C: Ord> PartialOrd for Queued<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> PartialEq for Queued<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl<S, C: Ord> Eq for Queued<S, C> {}
+
+/// Connected regions of a matrix, see `Fill::components`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Components {
+    /// Region index of every cell, `None` for cells outside all regions.
+    pub labels: Grid<Option<usize>>,
+    /// Members of each region, in discovery order.
+    pub regions: Vec<Vec<Coord>>,
+}
+
+impl Components {
+    pub fn sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        self.regions.iter().map(Vec::len)
+    }
+
+    #[allow(dead_code)]
+    pub fn label(&self, coord: Coord) -> Option<usize> {
+        self.labels.get_coord(coord).flatten()
+    }
+}
+
+/// Multi-source BFS result, see `Fill::distances`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Distances {
+    /// Steps from the nearest source, `None` for unreached cells.
+    pub dist: Grid<Option<usize>>,
+    /// Cells grouped by distance, each layer in discovery order.
+    pub layers: Vec<Vec<Coord>>,
+}
+
+impl Distances {
+    #[allow(dead_code)]
+    pub fn get(&self, coord: Coord) -> Option<usize> {
+        self.dist.get_coord(coord).flatten()
+    }
+
+    /// Distance of the farthest reached cell.
+    #[allow(dead_code)]
+    pub fn max(&self) -> Option<usize> {
+        self.layers.len().checked_sub(1)
+    }
+}
+
+pub trait Fill: Matrix {
+    /// Cells reachable from `start` through cells whose value passes
+    /// `passable`. Empty if `start` itself doesn't.
+    fn flood_fill<P>(&self, start: Coord, passable: P, diagonals: bool) -> Vec<Coord>
+    where
+        P: Fn(Self::Item) -> bool;
+
+    fn components<P>(&self, passable: P, diagonals: bool) -> Components
+    where
+        P: Fn(Self::Item) -> bool;
+
+    /// Steps from every cell to the nearest of `sources`, moving only onto
+    /// cells whose value passes `passable`. Sources count as distance 0
+    /// whatever their value.
+    #[allow(dead_code)]
+    fn distances<P>(
         &self,
-        init_coord: Coord,
-        mut initial_st: St,
-        mut walk_fn: F,
-        neighbors_fn: G,
-    ) -> St
+        sources: impl IntoIterator<Item = Coord>,
+        passable: P,
+        diagonals: bool,
+    ) -> Distances
     where
-        F: FnMut(&mut St, Option<Coord>, Coord, M::Item),
-        G: Fn(Self::Index) -> I,
-        I: IntoIterator<Item = Self::Index>,
+        P: Fn(Self::Item) -> bool;
+}
+
+impl<M: Matrix> Fill for M {
+    fn flood_fill<P>(&self, start: Coord, passable: P, diagonals: bool) -> Vec<Coord>
+    where
+        P: Fn(M::Item) -> bool,
+    {
+        if !self.get_coord(start).is_some_and(&passable) {
+            return vec![];
+        }
+        self.walk(
+            start,
+            vec![],
+            |region, _, coord, _| region.push(coord),
+            |coord| {
+                self.neighbors(coord, diagonals)
+                    .into_iter()
+                    .filter_map(|(c, v)| passable(v).then_some(c))
+            },
+        )
+    }
+
+    fn components<P>(&self, passable: P, diagonals: bool) -> Components
+    where
+        P: Fn(M::Item) -> bool,
     {
-        let mut visited = HashSet::<Coord>::with_capacity(self.size());
-        let mut queue = VecDeque::new();
-        queue.push_back((init_coord, None));
-        while let Some((cur, prev)) = queue.pop_front() {
-            if !visited.insert(cur) {
+        let mut labels = Grid::new(self.width(), self.height(), None);
+        let mut regions = vec![];
+        for (coord, v) in self.flat_iter() {
+            if labels[coord].is_some() || !passable(v) {
                 continue;
             }
-            walk_fn(&mut initial_st, prev, cur, self.get_coord(cur).unwrap());
-            queue.extend(neighbors_fn(cur).into_iter().map(|next| (next, Some(cur))));
+            let region = self.flood_fill(coord, &passable, diagonals);
+            for &c in region.iter() {
+                labels[c] = Some(regions.len());
+            }
+            regions.push(region);
+        }
+        Components { labels, regions }
+    }
+
+    fn distances<P>(
+        &self,
+        sources: impl IntoIterator<Item = Coord>,
+        passable: P,
+        diagonals: bool,
+    ) -> Distances
+    where
+        P: Fn(M::Item) -> bool,
+    {
+        let mut dist = Grid::new(self.width(), self.height(), None);
+        let mut layer = vec![];
+        for c in sources {
+            if self.get_coord(c).is_some() && dist[c].is_none() {
+                dist[c] = Some(0);
+                layer.push(c);
+            }
+        }
+        let mut layers = vec![];
+        while !layer.is_empty() {
+            let mut next = vec![];
+            for &coord in layer.iter() {
+                for (c, v) in self.neighbors(coord, diagonals) {
+                    if dist[c].is_none() && passable(v) {
+                        dist[c] = Some(layers.len() + 1);
+                        next.push(c);
+                    }
+                }
+            }
+            layers.push(std::mem::replace(&mut layer, next));
+        }
+        Distances { dist, layers }
+    }
+}
+
+fn bfs<Ix, T, V, F, G, I, St>(
+    init: Ix,
+    mut st: St,
+    value_fn: V,
+    mut walk_fn: F,
+    neighbors_fn: G,
+    capacity: usize,
+) -> St
+where
+    Ix: Copy + Eq + Hash,
+    V: Fn(Ix) -> T,
+    F: FnMut(&mut St, Option<Ix>, Ix, T),
+    G: Fn(Ix) -> I,
+    I: IntoIterator<Item = Ix>,
+{
+    let mut visited = HashSet::<Ix>::with_capacity(capacity);
+    let mut queue = VecDeque::new();
+    queue.push_back((init, None));
+    while let Some((cur, prev)) = queue.pop_front() {
+        if !visited.insert(cur) {
+            continue;
+        }
+        walk_fn(&mut st, prev, cur, value_fn(cur));
+        queue.extend(neighbors_fn(cur).into_iter().map(|next| (next, Some(cur))));
+    }
+    st
+}
+
+/// Order in which `search` expands states.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum Order {
+    Breadth,
+    Depth,
+}
+
+/// Visits every state reachable from `start` once, passing `visit` the state
+/// and its depth in the search tree. Returns the value `visit` breaks with.
+#[allow(dead_code)]
+pub fn search<S, N, I, V, B>(start: S, order: Order, mut successors: N, mut visit: V) -> Option<B>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    V: FnMut(&S, usize) -> ControlFlow<B>,
+{
+    let mut visited = HashSet::new();
+    let mut frontier = VecDeque::from([(start, 0)]);
+    loop {
+        let next = match order {
+            Order::Breadth => frontier.pop_front(),
+            Order::Depth => frontier.pop_back(),
+        };
+        let (state, depth) = next?;
+        if visited.contains(&state) {
+            continue;
+        }
+        if let ControlFlow::Break(b) = visit(&state, depth) {
+            return Some(b);
+        }
+        let children = successors(&state)
+            .into_iter()
+            .filter(|s| !visited.contains(s))
+            .map(|s| (s, depth + 1));
+        match order {
+            Order::Breadth => frontier.extend(children),
+            // Pushed in reverse so the first successor is expanded first.
+            Order::Depth => {
+                let children = children.collect::<Vec<_>>();
+                frontier.extend(children.into_iter().rev());
+            }
+        }
+        visited.insert(state);
+    }
+}
+
+/// Depth-limited DFS with limits `0..=max_depth`, visiting the states at
+/// exactly the current limit on each pass. Only cycles along the current
+/// path are cut, so memory stays proportional to the depth.
+#[allow(dead_code)]
+pub fn iddfs<S, N, I, V, B>(
+    start: S,
+    max_depth: usize,
+    mut successors: N,
+    mut visit: V,
+) -> Option<B>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    V: FnMut(&S, usize) -> ControlFlow<B>,
+{
+    let mut path = HashSet::new();
+    for limit in 0..=max_depth {
+        match depth_limited(&start, 0, limit, &mut path, &mut successors, &mut visit) {
+            ControlFlow::Break(b) => return Some(b),
+            ControlFlow::Continue(false) => return None,
+            ControlFlow::Continue(true) => {}
+        }
+    }
+    None
+}
+
+/// Continues with whether any state at depth `limit` was reached.
+#[allow(dead_code)]
+fn depth_limited<S, N, I, V, B>(
+    state: &S,
+    depth: usize,
+    limit: usize,
+    path: &mut HashSet<S>,
+    successors: &mut N,
+    visit: &mut V,
+) -> ControlFlow<B, bool>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    V: FnMut(&S, usize) -> ControlFlow<B>,
+{
+    if depth == limit {
+        visit(state, depth)?;
+        return ControlFlow::Continue(true);
+    }
+    path.insert(state.clone());
+    let mut reached = false;
+    for next in successors(state) {
+        if !path.contains(&next) {
+            match depth_limited(&next, depth + 1, limit, path, successors, visit) {
+                ControlFlow::Break(b) => {
+                    path.remove(state);
+                    return ControlFlow::Break(b);
+                }
+                ControlFlow::Continue(r) => reached |= r,
+            }
         }
-        initial_st
     }
+    path.remove(state);
+    ControlFlow::Continue(reached)
+}
+
+#[test]
+fn test_walk_torus() {
+    use super::matrix::Boundary;
+
+    let grid = vec![vec![1, 0, 1], vec![0, 0, 0], vec![1, 0, 1]].with_boundary(Boundary::Wrap);
+    let region = grid.walk(
+        Coord::new(0, 0),
+        0,
+        |n, _, _, _| *n += 1,
+        |c| grid.neighbors4(c).filter(|&(_, v)| v == 1).map(|(c, _)| c),
+    );
+    assert_eq!(region, 4);
+}
+
+#[test]
+fn test_walk_sparse() {
+    use super::point::Point;
+
+    let grid = SparseGrid::parse("##.\n.#.\n.##\n", false, |c| Some(c == '#'));
+    let region = grid.walk(
+        Point::new(0, 0),
+        vec![],
+        |region, _, p, _| region.push(p),
+        |p| p.neighbors(false).filter(|&n| *grid.get(n)),
+    );
+    assert_eq!(region.len(), 5);
+}
+
+#[test]
+fn test_walk_sparse_n() {
+    use super::point::PointN;
+
+    let mut grid = SparseGrid::new(false);
+    for p in [[0, 0, 0], [1, 0, 0], [1, 1, 0], [1, 1, 1], [3, 3, 3]] {
+        grid.insert(PointN(p), true);
+    }
+    let region = grid.walk(
+        PointN([0, 0, 0]),
+        0,
+        |n, _, _, _| *n += 1,
+        |p| p.neighbors(false).filter(|&n| *grid.get(n)),
+    );
+    assert_eq!(region, 4);
+}
+
+#[test]
+fn test_components() {
+    let xs = vec![vec![1, 1, 0, 1], vec![0, 0, 1, 1], vec![1, 0, 0, 0]];
+    let parts = xs.components(|v| v == 1, false);
+    assert_eq!(parts.sizes().collect::<Vec<_>>(), [2, 3, 1]);
+    assert_eq!(parts.label(Coord::new(3, 0)), Some(1));
+    assert_eq!(parts.label(Coord::new(2, 0)), None);
+    assert_eq!(parts.regions[2], [Coord::new(0, 2)]);
+    assert_eq!(
+        xs.components(|v| v == 1, true).sizes().collect::<Vec<_>>(),
+        [5, 1]
+    );
+    assert_eq!(xs.flood_fill(Coord::new(0, 1), |v| v == 1, true), []);
+    assert_eq!(xs.flood_fill(Coord::new(0, 1), |v| v == 0, false).len(), 5);
+}
+
+#[test]
+fn test_shortest_paths() {
+    let risk = vec![vec![1, 1, 6], vec![1, 3, 8], vec![2, 1, 3]];
+    let goal = Coord::new(2, 2);
+    let neighbors = |&c: &Coord| risk.neighbors4(c).map(|(c, _)| c);
+    let paths = risk.dijkstra(Coord::new(0, 0), neighbors, |_, _, v| v, |&c| c == goal);
+    assert_eq!(paths.distance(), Some(7));
+    assert_eq!(
+        paths.path().unwrap(),
+        [(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)].map(|(x, y)| Coord::new(x, y))
+    );
+    let manhattan = |c: &Coord| (goal.x - c.x) + (goal.y - c.y);
+    let guided = risk.astar(
+        Coord::new(0, 0),
+        neighbors,
+        |_, _, v| v,
+        |&c| c == goal,
+        manhattan,
+    );
+    assert_eq!(guided.distance(), Some(7));
+    assert!(guided.dist.len() <= paths.dist.len());
+
+    let all = risk.dijkstra(Coord::new(0, 0), neighbors, |_, _, v| v, |_| false);
+    assert_eq!(all.goal, None);
+    assert_eq!(all.dist.len(), 9);
+    assert_eq!(all.dist[&Coord::new(2, 0)], 7);
+
+    // Reach 10 from 1 by doubling (cost 1) or incrementing (cost 2).
+    let paths = dijkstra(
+        1u32,
+        |&n| [n * 2, n + 1].into_iter().filter(|&m| m <= 10),
+        |&a, &b| if b == a * 2 { 1 } else { 2 },
+        |&n| n == 10,
+    );
+    assert_eq!(paths.distance(), Some(5));
+    assert_eq!(paths.path(), Some(vec![1, 2, 4, 5, 10]));
+    assert_eq!(paths.path_to(&11), None);
+}
+
+#[test]
+fn test_search() {
+    // Two jugs of 3 and 5 litres; measure 4.
+    let pour = |&(a, b): &(u8, u8)| {
+        let ab = a.min(5 - b);
+        let ba = b.min(3 - a);
+        [
+            (3, b),
+            (a, 5),
+            (0, b),
+            (a, 0),
+            (a - ab, b + ab),
+            (a + ba, b - ba),
+        ]
+    };
+    let goal = |&(_, b): &(u8, u8), depth| {
+        if b == 4 {
+            ControlFlow::Break(depth)
+        } else {
+            ControlFlow::Continue(())
+        }
+    };
+    assert_eq!(search((0, 0), Order::Breadth, pour, goal), Some(6));
+    assert!(search((0, 0), Order::Depth, pour, goal).is_some_and(|d| d >= 6));
+    assert_eq!(iddfs((0, 0), 10, pour, goal), Some(6));
+    assert_eq!(iddfs((0, 0), 5, pour, goal), None);
+
+    let mut order = vec![];
+    let tree = |&n: &u32| (n < 4).then(|| [2 * n, 2 * n + 1]).into_iter().flatten();
+    let none = search(1, Order::Depth, tree, |&n, _| {
+        order.push(n);
+        ControlFlow::<()>::Continue(())
+    });
+    assert_eq!(none, None);
+    assert_eq!(order, [1, 2, 4, 5, 3, 6, 7]);
+    // Stops once a pass reaches nothing new at its limit.
+    let mut passes = vec![];
+    iddfs(1, 100, tree, |&n, d| {
+        passes.push((n, d));
+        ControlFlow::<()>::Continue(())
+    });
+    assert_eq!(passes.len(), 7);
+}
+
+#[test]
+fn test_distances() {
+    let maze = Grid::parse("S.#.\n..#.\n...S\n", |c| c);
+    let sources = maze.flat_iter().filter(|&(_, v)| v == 'S').map(|(c, _)| c);
+    let d = maze.distances(sources, |v| v != '#', false);
+    assert_eq!(d.max(), Some(2));
+    assert_eq!(d.get(Coord::new(3, 0)), Some(2));
+    assert_eq!(d.get(Coord::new(1, 1)), Some(2));
+    assert_eq!(d.get(Coord::new(2, 0)), None);
+    assert_eq!(d.layers.iter().map(Vec::len).collect::<Vec<_>>(), [2, 4, 4]);
+
+    let d = maze.distances([Coord::new(0, 0)], |v| v != '#', true);
+    assert_eq!(d.get(Coord::new(3, 0)), Some(4));
+    assert_eq!(maze.distances([], |_| true, false).max(), None);
 }
@@ -16,11 +16,11 @@ impl Run for Runner {
 
     type Output = usize;
 
-    fn part1(&self, input: &Self::Input) -> Self::Output {
+    fn part1(&self, input: &Self::Input<'_>) -> Self::Output {
         increases(input)
     }
 
-    fn part2(&self, input: &Self::Input) -> Self::Output {
+    fn part2(&self, input: &Self::Input<'_>) -> Self::Output {
         let sums = input
             .windows(3)
             .map(|w| w.iter().sum())
@@ -10,7 +10,7 @@ impl Run for Runner {
 
     type Output = usize;
 
-    fn part1(&self, input: &Self::Input) -> Self::Output {
+    fn part1(&self, input: &Self::Input<'_>) -> Self::Output {
         let (x, y) = input.iter().fold((0, 0), |(x, y), step| match step.dir {
             Dir::Forward => (x + step.mag, y),
             Dir::Up => (x, y - step.mag),
@@ -19,7 +19,7 @@ impl Run for Runner {
         x * y
     }
 
-    fn part2(&self, input: &Self::Input) -> Self::Output {
+    fn part2(&self, input: &Self::Input<'_>) -> Self::Output {
         let (x, y, _) = input
             .iter()
             .fold((0, 0, 0), |(x, y, aim), step| match step.dir {
@@ -1,13 +1,14 @@
-use crate::common::matrix::Matrix;
+use crate::common::dir::Dir4;
+use crate::common::matrix::{Coord, Line, Matrix};
 use crate::common::run::Run;
 
 pub struct Runner {}
 
 impl Run for Runner {
-    type Input = Vec<Vec<usize>>;
+    type Input<'a> = Vec<Vec<usize>>;
     type Output = usize;
 
-    fn parse(&self, s: &str) -> Self::Input {
+    fn parse<'a>(&self, s: &'a str) -> Self::Input<'a> {
         s.lines()
             .map(|line| {
                 line.chars()
@@ -21,26 +22,25 @@ impl Run for Runner {
             .collect()
     }
 
-    fn part1(&self, input: &Self::Input) -> Self::Output {
+    fn part1(&self, input: &Self::Input<'_>) -> Self::Output {
         let gamma = input
-            .transpose()
-            .iter()
-            .map(|col| most_common(col))
+            .cols()
+            .map(most_common)
             .fold(0, |acc, cur| acc << 1 | cur);
         let epsilon = !gamma & ((1 << input[0].len()) - 1);
         gamma * epsilon
     }
 
-    fn part2(&self, input: &Self::Input) -> Self::Output {
+    fn part2(&self, input: &Self::Input<'_>) -> Self::Output {
         let mut oxy: Vec<Vec<usize>> = input.clone();
         let mut co2: Vec<Vec<usize>> = input.clone();
         for j in 0..input[0].len() {
             if oxy.len() > 1 {
-                let most_common_jth_bit = most_common(&oxy.transpose()[j]);
+                let most_common_jth_bit = most_common(column(&oxy, j));
                 oxy.retain(|row| row[j] == most_common_jth_bit);
             }
             if co2.len() > 1 {
-                let most_common_jth_bit = most_common(&co2.transpose()[j]);
+                let most_common_jth_bit = most_common(column(&co2, j));
                 co2.retain(|row| row[j] != most_common_jth_bit);
             }
             if oxy.len() == 1 && co2.len() == 1 {
@@ -55,6 +55,11 @@ fn bits_to_usize(xs: &[usize]) -> usize {
     xs.iter().fold(0, |acc, &cur| acc << 1 | cur)
 }
 
-fn most_common(xs: &[usize]) -> usize {
-    (xs.iter().filter(|&&b| b == 1).count() >= (xs.len() + 1) / 2).into()
+fn column(m: &Vec<Vec<usize>>, j: usize) -> Line<'_, Vec<Vec<usize>>> {
+    m.line(Coord::new(j, 0), Dir4::Down)
+}
+
+fn most_common<M: Matrix<Item = usize>>(xs: Line<'_, M>) -> usize {
+    let (ones, len) = xs.fold((0, 0usize), |(ones, len), (_, b)| (ones + b, len + 1));
+    (ones >= len.div_ceil(2)).into()
 }
@@ -1,16 +1,16 @@
-use std::ops::Not;
 use std::str::FromStr;
 
-use crate::common::matrix::Matrix;
+use crate::common::bitgrid::BitGrid;
+use crate::common::matrix::{Coord, Matrix};
 use crate::common::run::Run;
 
 pub struct Runner {}
 
 impl Run for Runner {
-    type Input = Bingo;
+    type Input<'a> = Bingo;
     type Output = usize;
 
-    fn parse(&self, s: &str) -> Self::Input {
+    fn parse<'a>(&self, s: &'a str) -> Self::Input<'a> {
         let mut it = s.split("\n\n");
         let draws = it
             .next()
@@ -28,13 +28,13 @@ impl Run for Runner {
         }
     }
 
-    fn part1(&self, input: &Self::Input) -> Self::Output {
+    fn part1(&self, input: &Self::Input<'_>) -> Self::Output {
         let mut bingo = input.clone();
         bingo.play_all();
         bingo.boards[bingo.wins[0]].score()
     }
 
-    fn part2(&self, input: &Self::Input) -> Self::Output {
+    fn part2(&self, input: &Self::Input<'_>) -> Self::Output {
         let mut bingo = input.clone();
         bingo.play_all();
         bingo.boards[*bingo.wins.last().unwrap()].score()
@@ -51,7 +51,7 @@ pub struct Bingo {
 #[derive(Clone)]
 pub struct Board {
     grid: Vec<Vec<usize>>,
-    marked: Vec<Vec<bool>>,
+    marked: BitGrid,
     last_draw: usize,
 }
 
@@ -75,8 +75,7 @@ impl Bingo {
 
 impl Board {
     fn bingo(&self) -> bool {
-        let check = |it: &[Vec<bool>]| it.iter().any(|row| row.iter().all(|&m| m));
-        check(&self.marked) || check(&self.marked.transpose())
+        self.marked.any_full_row() || self.marked.any_full_col()
     }
 
     fn play(&mut self, draw: usize) {
@@ -84,7 +83,7 @@ impl Board {
             for (j, &num) in row.iter().enumerate() {
                 if num == draw {
                     self.last_draw = draw;
-                    self.marked[i][j] = true;
+                    self.marked.set(Coord::new(j, i), true);
                     return;
                 }
             }
@@ -93,10 +92,8 @@ impl Board {
 
     fn score(&self) -> usize {
         self.grid
-            .iter()
-            .flatten()
-            .zip(self.marked.iter().flatten())
-            .filter_map(|(num, marked)| marked.not().then_some(num))
+            .flat_iter()
+            .filter_map(|(c, num)| (!self.marked.get(c)).then_some(num))
             .sum::<usize>()
             * self.last_draw
     }
@@ -115,7 +112,7 @@ impl FromStr for Board {
             .collect();
         Ok(Board {
             grid,
-            marked: vec![vec![false; 5]; 5],
+            marked: BitGrid::new(5, 5),
             last_draw: 0,
         })
     }
@@ -10,11 +10,11 @@ impl Run for Runner {
     vec_input!(Line);
     type Output = usize;
 
-    fn part1(&self, input: &Self::Input) -> Self::Output {
+    fn part1(&self, input: &Self::Input<'_>) -> Self::Output {
         solve(input, false)
     }
 
-    fn part2(&self, input: &Self::Input) -> Self::Output {
+    fn part2(&self, input: &Self::Input<'_>) -> Self::Output {
         solve(input, true)
     }
 }
@@ -24,8 +24,7 @@ fn solve(lines: &[Line], diagonals: bool) -> usize {
         .iter()
         .flat_map(|line| line.point_set(diagonals))
         .counter()
-        .into_values()
-        .filter(|&c| c > 1)
+        .at_least(2)
         .count()
 }
 
@@ -1,6 +1,5 @@
-use std::iter::{once, successors};
-
-use crate::common::counter::{Countable, CounterSum};
+use crate::common::counter::DenseCounter;
+use crate::common::intmatrix::IntMatrix;
 use crate::common::run::Run;
 use crate::vec_input;
 
@@ -10,38 +9,38 @@ impl Run for Runner {
     vec_input!(usize, ',');
     type Output = usize;
 
-    fn part1(&self, input: &Self::Input) -> Self::Output {
+    fn part1(&self, input: &Self::Input<'_>) -> Self::Output {
         const N: usize = 80;
         solve(input, N)
     }
 
-    fn part2(&self, input: &Self::Input) -> Self::Output {
+    fn part2(&self, input: &Self::Input<'_>) -> Self::Output {
         const N: usize = 256;
         solve(input, N)
     }
 }
 
 fn solve(xs: &[usize], n: usize) -> usize {
-    let xs = xs.iter().copied().counter();
-    successors(Some(xs), |xs| {
-        let zeroes = *xs.get(&0).unwrap_or(&0);
-        Some(
-            xs.iter()
-                .filter_map(|(&k, &v)| (k != 0).then_some((k - 1, v)))
-                .chain(once((6, zeroes)))
-                .chain(once((8, zeroes)))
-                .counter_sum(),
-        )
-    })
-    .nth(n)
-    .unwrap()
-    .values()
-    .sum()
+    let fish = xs.iter().copied().collect::<DenseCounter<9>>();
+    let counts = (0..9).map(|timer| fish.get(&timer)).collect::<Vec<_>>();
+    let day = IntMatrix::transition(9, |timer| match timer {
+        0 => vec![6, 8],
+        t => vec![t - 1],
+    });
+    day.pow(n as u64).apply(&counts).into_iter().sum()
+}
+
+#[test]
+fn test_solve() {
+    let input = [3, 4, 3, 1, 2];
+    assert_eq!(solve(&input, 18), 26);
+    assert_eq!(solve(&input, 80), 5934);
+    assert_eq!(solve(&input, 256), 26984457539);
 }
 
 #[test]
-fn test() {
-    let input = vec![3, 4, 3, 1, 2];
-    let ans = solve(&input, 18);
-    dbg!(ans);
+fn test_spawning() {
+    // Fish at timer 0 used to underflow in `k - 1` in debug builds.
+    assert_eq!(solve(&[0], 1), 2);
+    assert_eq!(solve(&[0, 0, 8], 7), 5);
 }
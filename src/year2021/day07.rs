@@ -8,7 +8,7 @@ where
     F: Fn(usize) -> usize,
 {
     let (&min, &max) = (input.iter().min().unwrap(), input.iter().max().unwrap());
-    (min..max)
+    (min..=max)
         .map(|target| {
             input
                 .iter()
@@ -23,11 +23,11 @@ impl Run for Runner {
     vec_input!(usize, ',');
     type Output = usize;
 
-    fn part1(&self, input: &Self::Input) -> Self::Output {
+    fn part1(&self, input: &Self::Input<'_>) -> Self::Output {
         solve(input, |x| x)
     }
 
-    fn part2(&self, input: &Self::Input) -> Self::Output {
+    fn part2(&self, input: &Self::Input<'_>) -> Self::Output {
         solve(input, |x| x * (x + 1) / 2)
     }
 }
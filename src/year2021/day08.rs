@@ -1,7 +1,7 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 use std::str::FromStr;
 
-use crate::common::reverse::Reverse;
+use crate::common::csp::Solver;
 use crate::common::run::Run;
 use crate::vec_input;
 
@@ -11,7 +11,7 @@ impl Run for Runner {
     vec_input!(Display);
     type Output = usize;
 
-    fn part1(&self, input: &Self::Input) -> Self::Output {
+    fn part1(&self, input: &Self::Input<'_>) -> Self::Output {
         input
             .iter()
             .map(|display| {
@@ -24,7 +24,7 @@ impl Run for Runner {
             .sum()
     }
 
-    fn part2(&self, input: &Self::Input) -> Self::Output {
+    fn part2(&self, input: &Self::Input<'_>) -> Self::Output {
         input
             .iter()
             .map(|display| {
@@ -39,6 +39,11 @@ impl Run for Runner {
 
 type Digit = HashSet<u8>;
 
+/// Lit segments of 0 through 9.
+const DIGITS: [&str; 10] = [
+    "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+];
+
 pub struct Display {
     digits: Vec<Digit>,
     displayed: Vec<Digit>,
@@ -46,73 +51,50 @@ pub struct Display {
 
 impl Display {
     fn solve(&self) -> Vec<u8> {
-        let mut map = HashMap::<u8, u8>::new();
-
-        // 1. unique freqs -> B(6), E(4), F(9).
-        let freqs = b"abcdefg"
-            .iter()
-            .copied()
-            .map(|seg| {
-                let freq = self.digits.iter().filter(|&d| d.contains(&seg)).count();
-                (freq, seg)
-            })
-            .collect::<HashMap<_, _>>();
-
-        map.insert(b'b', freqs[&6]);
-        map.insert(b'e', freqs[&4]);
-        map.insert(b'f', freqs[&9]);
-
-        let mut do_digit = |seg: u8, found: &[u8]| {
-            let digit = self
-                .digits
+        // Variable `i` is the segment wire `i` lights up.
+        let mut wiring = Solver::new(7, 7);
+        wiring.all_different(0..7);
+        // A pattern's length narrows it to the digits that light that many
+        // segments: its wires light one of theirs, the others none they share.
+        for digit in self.digits.iter() {
+            let same_len = DIGITS
                 .iter()
-                .find(|d| d.len() == found.len() + 1)
-                .unwrap();
-            let unmapped_segs = digit
-                .iter()
-                .filter(|&&seg| !map.reverse().contains_key(&seg))
-                .collect::<Vec<_>>();
-            assert_eq!(unmapped_segs.len(), 1);
-            map.insert(seg, *unmapped_segs[0]);
-        };
-
-        // 2. 1 (CF) -> C.
-        do_digit(b'c', b"f");
-
-        // 3. 7 (ACF) -> A.
-        do_digit(b'a', b"cf");
-
-        // 4. 4 (BCDF) -> D.
-        do_digit(b'd', b"bcf");
-
-        // 5. 8 (ABCDEFG) -> G.
-        do_digit(b'g', b"abcdef");
-
-        let map = map.reverse();
+                .filter(|d| d.len() == digit.len())
+                .map(|d| d.bytes().fold(0u8, |acc, s| acc | 1 << wire(s)));
+            let any = same_len.clone().fold(0, |acc, d| acc | d);
+            let all = same_len.fold(!0, |acc, d| acc & d);
+            for w in b'a'..=b'g' {
+                let segs = if digit.contains(&w) { any } else { !all };
+                wiring.restrict(wire(w), |s| segs >> s & 1 == 1);
+            }
+        }
+        for digit in self.digits.iter() {
+            wiring.constraint(digit.iter().map(|&w| wire(w)), |segs| {
+                decode(segs).is_some()
+            });
+        }
+        let segs = wiring.solve().expect("no consistent wiring");
 
         self.displayed
             .iter()
-            .map(|displayed| canonical(displayed, &map))
+            .map(|digit| {
+                let lit = digit.iter().map(|&w| segs[wire(w)]).collect::<Vec<_>>();
+                decode(&lit).unwrap()
+            })
             .collect()
     }
 }
 
-fn canonical(digit: &Digit, map: &HashMap<u8, u8>) -> u8 {
-    let mut s = digit.iter().map(|c| map[c]).collect::<Vec<_>>();
-    s.sort();
-    let s = std::str::from_utf8(&s).unwrap();
-    HashMap::from([
-        ("abcefg", 0),
-        ("cf", 1),
-        ("acdeg", 2),
-        ("acdfg", 3),
-        ("bcdf", 4),
-        ("abdfg", 5),
-        ("abdefg", 6),
-        ("acf", 7),
-        ("abcdefg", 8),
-        ("abcdfg", 9),
-    ])[&s]
+fn wire(w: u8) -> usize {
+    (w - b'a') as usize
+}
+
+fn decode(segs: &[usize]) -> Option<u8> {
+    let lit = segs.iter().fold(0u8, |acc, &s| acc | 1 << s);
+    DIGITS
+        .iter()
+        .position(|d| d.bytes().fold(0u8, |acc, s| acc | 1 << wire(s)) == lit)
+        .map(|i| i as u8)
 }
 
 impl FromStr for Display {
@@ -146,10 +128,22 @@ impl FromStr for Display {
 // 8: ABCDEFG 7*
 // 9: ABCDFG  6
 
-// A: 02356789  8
-// B: 045689    6*
-// C: 01234789  8
-// D: 2345689   7
-// E: 0268      4*
-// F: 013456789 9*
-// G: 0235689   7
+#[test]
+fn test() {
+    let sample = "\
+be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe
+edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc
+fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg
+fbegcd cbd adcefb dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb
+aecbfdg fbg gf bafeg dbefa fcge gcbea fcaegb dgceab fcbdga | gecf egdcabf bgf bfgea
+fgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg bafgc acf | gebdcfa ecba ca fadegcb
+dbcfg fgd bdegcaf fgec aegbdf ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe
+bdfegc cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba cbgef
+egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb
+gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
+";
+    let runner = Runner {};
+    let input = runner.parse(sample);
+    assert_eq!(runner.part1(&input), 26);
+    assert_eq!(runner.part2(&input), 61229);
+}
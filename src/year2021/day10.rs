@@ -14,10 +14,10 @@ fn closer(c: char) -> char {
 }
 
 impl Run for Runner {
-    vec_input!(String);
+    vec_input!(&str);
     type Output = usize;
 
-    fn part1(&self, input: &Self::Input) -> Self::Output {
+    fn part1(&self, input: &Self::Input<'_>) -> Self::Output {
         input
             .iter()
             .map(|line| {
@@ -49,7 +49,7 @@ impl Run for Runner {
             .sum::<usize>()
     }
 
-    fn part2(&self, input: &Self::Input) -> Self::Output {
+    fn part2(&self, input: &Self::Input<'_>) -> Self::Output {
         let mut scores = input
             .iter()
             .filter_map(|line| {